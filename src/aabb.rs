@@ -0,0 +1,94 @@
+use crate::vec3::Point3;
+use crate::Float;
+use crate::Ray;
+
+// Axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub minimum: Point3,
+    pub maximum: Point3,
+}
+
+impl Aabb {
+    pub fn new(minimum: Point3, maximum: Point3) -> Aabb {
+        Aabb { minimum, maximum }
+    }
+
+    // Slab test: intersect the ray's [t_min, t_max] interval with the interval
+    // where it lies between each pair of axis-aligned planes.
+    pub fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> bool {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+        for a in 0..3 {
+            let inv_d = 1.0 / r.dir[a];
+            let mut t0 = (self.minimum[a] - r.orig[a]) * inv_d;
+            let mut t1 = (self.maximum[a] - r.orig[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.minimum + self.maximum)
+    }
+
+    pub fn surface_area(&self) -> Float {
+        let d = self.maximum - self.minimum;
+        2.0 * (d.x() * d.y() + d.y() * d.z() + d.z() * d.x())
+    }
+}
+
+pub fn surrounding_box(a: &Aabb, b: &Aabb) -> Aabb {
+    Aabb::new(a.minimum.min(b.minimum), a.maximum.max(b.maximum))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::Vec3;
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0))
+    }
+
+    #[test]
+    fn test_hit_pierces_box() {
+        let r = Ray::new(Point3::new(0.5, 0.5, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(unit_box().hit(&r, 0.0, Float::INFINITY));
+        // pointing the other way, the box is behind the ray
+        let r = Ray::new(Point3::new(0.5, 0.5, -2.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(!unit_box().hit(&r, 0.0, Float::INFINITY));
+    }
+
+    #[test]
+    fn test_hit_misses_box() {
+        let r = Ray::new(Point3::new(2.0, 0.5, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(!unit_box().hit(&r, 0.0, Float::INFINITY));
+        // the box lies beyond t_max
+        let r = Ray::new(Point3::new(0.5, 0.5, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(!unit_box().hit(&r, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_centroid_and_surface_area() {
+        let b = Aabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(b.centroid(), Point3::new(0.5, 1.0, 1.5));
+        assert_eq!(b.surface_area(), 22.0);
+    }
+
+    #[test]
+    fn test_surrounding_box() {
+        let other = Aabb::new(Point3::new(-1.0, 0.5, 0.5), Point3::new(0.5, 3.0, 0.75));
+        assert_eq!(
+            surrounding_box(&unit_box(), &other),
+            Aabb::new(Point3::new(-1.0, 0.0, 0.0), Point3::new(1.0, 3.0, 1.0))
+        );
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "png")]
+use std::path::Path;
+
+#[cfg(feature = "png")]
+use image::ImageError;
+
+use crate::vec3::{Color, COLOR_WHITE};
+use crate::Float;
+use crate::{Ray, Sphere};
+
+// The radiance of rays that escape the scene without hitting anything.
+pub trait Background: Send + Sync {
+    fn color(&self, r: &Ray) -> Color;
+}
+
+// The same color in every direction.
+pub struct SolidBackground(pub Color);
+
+impl Background for SolidBackground {
+    fn color(&self, _r: &Ray) -> Color {
+        self.0
+    }
+}
+
+// A vertical blend from `bottom` (looking straight down) to `top` (looking straight up).
+pub struct GradientSky {
+    pub bottom: Color,
+    pub top: Color,
+}
+
+impl GradientSky {
+    pub fn new(bottom: Color, top: Color) -> Self {
+        GradientSky { bottom, top }
+    }
+}
+
+impl Default for GradientSky {
+    // white to light blue
+    fn default() -> Self {
+        GradientSky::new(COLOR_WHITE, Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Background for GradientSky {
+    fn color(&self, r: &Ray) -> Color {
+        let t = 0.5 * (r.direction().unit_vector().y() + 1.0);
+        self.bottom.lerp(self.top, t)
+    }
+}
+
+// An equirectangular (latitude-longitude) image wrapped around the scene.
+pub struct EnvironmentMap {
+    width: usize,
+    height: usize,
+    // rows top to bottom
+    pixels: Vec<Color>,
+}
+
+impl EnvironmentMap {
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "expected {width}x{height} pixels"
+        );
+        EnvironmentMap {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Loads any image format `image` was built with. With the `exr` feature that includes
+    // high dynamic range .hdr and .exr files, whose radiance isn't limited to [0, 1].
+    #[cfg(feature = "png")]
+    pub fn open(path: &Path) -> Result<Self, ImageError> {
+        let image = image::open(path)?.into_rgb32f();
+        let (width, height) = image.dimensions();
+        let pixels = image
+            .pixels()
+            .map(|p| Color::new(p.0[0] as Float, p.0[1] as Float, p.0[2] as Float))
+            .collect();
+        Ok(Self::from_pixels(width as usize, height as usize, pixels))
+    }
+}
+
+impl Background for EnvironmentMap {
+    fn color(&self, r: &Ray) -> Color {
+        // If we have no image data, return magenta as a debugging aid.
+        let dir = r.direction().unit_vector();
+        if self.pixels.is_empty() || !dir.x().is_finite() {
+            return Color::new(1.0, 0.0, 1.0);
+        }
+
+        // Same mapping as the sphere's uv; images store rows top to bottom.
+        let (u, v) = Sphere::get_sphere_uv(dir);
+        let i = ((u * self.width as Float) as usize).min(self.width - 1);
+        let j = (((1.0 - v) * self.height as Float) as usize).min(self.height - 1);
+        self.pixels[j * self.width + i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::{Point3, Vec3, COLOR_BLACK};
+
+    fn ray(dir: Vec3) -> Ray {
+        Ray::new(Point3::new(0.0, 0.0, 0.0), dir)
+    }
+
+    #[test]
+    fn test_solid_background() {
+        let black = SolidBackground(COLOR_BLACK);
+        for dir in [
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, -2.0, 0.5),
+            Vec3::new(0.0, 0.0, -1.0),
+        ] {
+            assert_eq!(black.color(&ray(dir)), COLOR_BLACK);
+        }
+    }
+
+    #[test]
+    fn test_gradient_varies_with_y() {
+        let sky = GradientSky::default();
+        assert_eq!(sky.color(&ray(Vec3::new(0.0, 2.0, 0.0))), sky.top);
+        assert_eq!(sky.color(&ray(Vec3::new(0.0, -1.0, 0.0))), sky.bottom);
+        assert_eq!(
+            sky.color(&ray(Vec3::new(1.0, 0.0, 0.0))),
+            Color::new(0.75, 0.85, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_environment_map_hemispheres() {
+        let sky = Color::new(0.2, 0.4, 8.0);
+        let ground = Color::new(0.3, 0.2, 0.1);
+        // two columns, one row per hemisphere
+        let env = EnvironmentMap::from_pixels(2, 2, vec![sky, sky, ground, ground]);
+
+        assert_eq!(env.color(&ray(Vec3::new(0.0, 1.0, 0.0))), sky);
+        assert_eq!(env.color(&ray(Vec3::new(0.3, 1.0, -0.2))), sky);
+        assert_eq!(env.color(&ray(Vec3::new(0.0, -1.0, 0.0))), ground);
+        assert_eq!(env.color(&ray(Vec3::new(-0.5, -1.0, 0.1))), ground);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_environment_map_open() {
+        let path = std::env::temp_dir().join("rt_test_environment_map.png");
+        let img = image::RgbImage::from_fn(1, 2, |_, y| {
+            if y == 0 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        });
+        img.save(&path).unwrap();
+        let env = EnvironmentMap::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            env.color(&ray(Vec3::new(0.0, 1.0, 0.0))),
+            Color::new(1.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            env.color(&ray(Vec3::new(0.0, -1.0, 0.0))),
+            Color::new(0.0, 0.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(feature = "png")]
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "png")]
+use image::{GrayImage, ImageError};
+
+use crate::consts::PI;
+use crate::util::{random_bounded, random_double};
+use crate::vec3::Vec3;
+use crate::Float;
+
+// The shape of a thin-lens camera's aperture, which out-of-focus highlights take on.
+#[derive(Clone, Default)]
+pub enum Bokeh {
+    #[default]
+    Circle,
+    // a regular polygon with this many sides, one corner pointing up
+    Polygon(u32),
+    Custom(Arc<ApertureMask>),
+}
+
+impl Bokeh {
+    // A random point on the aperture, scaled so the lens spans [-1, 1] in x and y.
+    pub fn sample(&self) -> Vec3 {
+        match self {
+            Bokeh::Circle => Vec3::new_random_in_unit_disk(),
+            Bokeh::Polygon(sides) => random_in_polygon(*sides),
+            Bokeh::Custom(mask) => mask.sample(),
+        }
+    }
+}
+
+// Uniform over the regular polygon with `sides` corners on the unit circle.
+fn random_in_polygon(sides: u32) -> Vec3 {
+    assert!(sides >= 3, "a polygonal aperture needs at least 3 sides");
+    // the polygon is a fan of equal triangles around the center: pick one, then a
+    // uniform point inside it
+    let wedge = 2.0 * PI / sides as Float;
+    let k = (random_double() * sides as Float).floor();
+    let corner = |i: Float| {
+        let angle = PI / 2.0 + wedge * i;
+        Vec3::new(angle.cos(), angle.sin(), 0.0)
+    };
+    let (a, b) = (corner(k), corner(k + 1.0));
+    let (r1, r2) = (random_double().sqrt(), random_double());
+    r1 * ((1.0 - r2) * a + r2 * b)
+}
+
+// An aperture drawn as a picture: its open pixels let light through. The picture covers
+// the square around the lens, top row first.
+pub struct ApertureMask {
+    width: usize,
+    height: usize,
+    open: Vec<bool>,
+}
+
+impl ApertureMask {
+    // Panics unless `open` holds `width * height` pixels and at least one is open.
+    pub fn new(width: usize, height: usize, open: Vec<bool>) -> Self {
+        assert_eq!(
+            open.len(),
+            width * height,
+            "mask size doesn't match its pixels"
+        );
+        assert!(open.contains(&true), "an aperture mask needs an open pixel");
+        ApertureMask {
+            width,
+            height,
+            open,
+        }
+    }
+
+    fn is_open(&self, x: Float, y: Float) -> bool {
+        let column = ((x + 1.0) / 2.0 * self.width as Float) as usize;
+        let row = ((1.0 - y) / 2.0 * self.height as Float) as usize;
+        self.open[row.min(self.height - 1) * self.width + column.min(self.width - 1)]
+    }
+
+    fn sample(&self) -> Vec3 {
+        loop {
+            let (x, y) = (random_bounded(-1.0, 1.0), random_bounded(-1.0, 1.0));
+            if self.is_open(x, y) {
+                return Vec3::new(x, y, 0.0);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "png")]
+impl ApertureMask {
+    // Pixels brighter than mid-gray are open.
+    pub fn from_image(image: &GrayImage) -> Self {
+        let (width, height) = image.dimensions();
+        let open = image.pixels().map(|p| p.0[0] >= 128).collect();
+        Self::new(width as usize, height as usize, open)
+    }
+
+    pub fn open(path: &Path) -> Result<Self, ImageError> {
+        Ok(Self::from_image(&image::open(path)?.to_luma8()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::seed_rng;
+    use crate::vec3::dot;
+
+    #[test]
+    fn test_hexagon_samples_stay_inside() {
+        seed_rng(3);
+        // a corner points up, so the flat sides face left and right, cos(30°) from the center
+        let apothem = Float::sqrt(3.0) / 2.0;
+        let mut widest: Float = 0.0;
+        for _ in 0..5000 {
+            let p = Bokeh::Polygon(6).sample();
+            widest = widest.max(p.x().abs());
+            for i in 0..6 {
+                let angle = (i as Float) * PI / 3.0;
+                let outward = Vec3::new(angle.cos(), angle.sin(), 0.0);
+                assert!(dot(p, outward) <= apothem + 1e-6, "{p:?} outside");
+            }
+        }
+        assert!(widest > 0.95 * apothem);
+    }
+
+    #[test]
+    fn test_custom_mask_samples_open_pixels() {
+        seed_rng(4);
+        // only the top right quarter is open
+        let mask = ApertureMask::new(2, 2, vec![false, true, false, false]);
+        let bokeh = Bokeh::Custom(Arc::new(mask));
+        for _ in 0..1000 {
+            let p = bokeh.sample();
+            assert!(p.x() >= 0.0 && p.y() >= 0.0, "{p:?} outside");
+        }
+    }
+}
@@ -0,0 +1,615 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::aabb::{surrounding_box, Aabb};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// How a `BvhNode` divides its objects between its two children.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SplitMethod {
+    // in half, by centroid along the axis where the centroids spread the most
+    #[default]
+    Median,
+    // wherever the surface area heuristic predicts the cheapest traversal, leaving a few
+    // objects together in one leaf where that's cheaper still
+    Sah,
+}
+
+// With SAH splits, nodes this small become a single leaf when splitting them wouldn't
+// pay for the extra box test.
+const SAH_MAX_LEAF_OBJECTS: usize = 4;
+
+// The cost of testing a node's box, relative to testing one object.
+const SAH_TRAVERSAL_COST: Float = 1.0;
+
+// Below this many objects, a parallel build finishes the subtree on the current thread.
+const PARALLEL_MIN_OBJECTS: usize = 1024;
+
+// A bounding volume hierarchy: a binary tree of boxes that lets rays skip whole groups
+// of objects they can't hit. Objects without a bounding box (like a `Plane`) are kept
+// beside the tree and tested against every ray.
+pub struct BvhNode {
+    // None when every object is unbounded
+    tree: Option<Node>,
+    // each with its position in the list the tree was built from
+    unbounded: Vec<(usize, Box<dyn Hittable>)>,
+}
+
+struct Node {
+    bbox: Aabb,
+    contents: Contents,
+}
+
+enum Contents {
+    // the objects, each with its position in the list the tree was built from
+    Leaf(Vec<(usize, Box<dyn Hittable>)>),
+    Split(Box<Node>, Box<Node>),
+}
+
+impl BvhNode {
+    // Builds a tree with median splits over objects that may move between time0 and time1.
+    // Hits report each object's index in `objects` as their `obj_id`, like a `HitList`.
+    // Panics if `objects` is empty.
+    pub fn new(objects: Vec<Box<dyn Hittable>>, time0: Float, time1: Float) -> Self {
+        Self::with_split(objects, time0, time1, SplitMethod::Median)
+    }
+
+    pub fn with_split(
+        objects: Vec<Box<dyn Hittable>>,
+        time0: Float,
+        time1: Float,
+        split: SplitMethod,
+    ) -> Self {
+        assert!(!objects.is_empty(), "a BvhNode needs at least one object");
+        let boxed = objects
+            .into_iter()
+            .enumerate()
+            .map(|(id, obj)| (obj.bounding_box(time0, time1), id, obj))
+            .collect();
+        Self::from_boxed(boxed, split, false)
+    }
+
+    // Like `with_split`, but builds large subtrees on several threads. The tree is the
+    // same as the serial build's.
+    #[cfg(feature = "rayon")]
+    pub fn with_split_parallel(
+        objects: Vec<Box<dyn Hittable>>,
+        time0: Float,
+        time1: Float,
+        split: SplitMethod,
+    ) -> Self {
+        assert!(!objects.is_empty(), "a BvhNode needs at least one object");
+        let boxed = objects
+            .into_par_iter()
+            .enumerate()
+            .map(|(id, obj)| (obj.bounding_box(time0, time1), id, obj))
+            .collect();
+        Self::from_boxed(boxed, split, true)
+    }
+
+    fn from_boxed(
+        objects: Vec<(Option<Aabb>, usize, Box<dyn Hittable>)>,
+        split: SplitMethod,
+        parallel: bool,
+    ) -> Self {
+        let mut bounded = Vec::with_capacity(objects.len());
+        let mut unbounded = Vec::new();
+        for (bbox, id, obj) in objects {
+            match bbox {
+                Some(bbox) => bounded.push((bbox, id, obj)),
+                None => unbounded.push((id, obj)),
+            }
+        }
+        BvhNode {
+            tree: (!bounded.is_empty()).then(|| Node::build(bounded, split, parallel)),
+            unbounded,
+        }
+    }
+
+    // The shape of the tree: how deep and how full it ended up.
+    pub fn stats(&self) -> BvhStats {
+        let mut stats = BvhStats {
+            node_count: 0,
+            leaf_count: 0,
+            max_depth: 0,
+            avg_leaf_size: 0.0,
+        };
+        if let Some(tree) = &self.tree {
+            let objects = tree.gather_stats(0, &mut stats);
+            stats.avg_leaf_size = objects as Float / stats.leaf_count as Float;
+        }
+        stats
+    }
+
+    // Wraps the tree so it counts the rays it's asked to hit and the nodes they visit,
+    // e.g. over a render.
+    pub fn with_traversal_stats(self) -> CountingBvh {
+        CountingBvh {
+            bvh: self,
+            rays: AtomicUsize::new(0),
+            nodes_visited: AtomicUsize::new(0),
+        }
+    }
+
+    // Like `hit`, also counting every node whose box was tested.
+    fn traverse(
+        &self,
+        r: &Ray,
+        t_min: Float,
+        t_max: Float,
+        visited: &mut usize,
+    ) -> Option<HitRecord> {
+        let mut closest = self
+            .tree
+            .as_ref()
+            .and_then(|tree| tree.traverse(r, t_min, t_max, visited));
+        for (obj_id, obj) in &self.unbounded {
+            let t_max = closest.as_ref().map_or(t_max, |rec| rec.t);
+            if let Some(rec) = obj.hit(r, t_min, t_max) {
+                closest = Some(HitRecord {
+                    obj_id: *obj_id,
+                    ..rec
+                });
+            }
+        }
+        closest
+    }
+}
+
+impl Node {
+    fn build(mut objects: Vec<Entry>, split: SplitMethod, parallel: bool) -> Self {
+        if objects.len() == 1 {
+            return Self::leaf(objects);
+        }
+
+        let mid = match split {
+            SplitMethod::Median => median_split(&mut objects),
+            SplitMethod::Sah => {
+                let (mid, split_cost) = sah_split(&mut objects);
+                let bbox = bounds(&objects);
+                let leaf_cost = objects.len() as Float * bbox.surface_area();
+                let split_cost = SAH_TRAVERSAL_COST * bbox.surface_area() + split_cost;
+                if objects.len() <= SAH_MAX_LEAF_OBJECTS && leaf_cost <= split_cost {
+                    return Self::leaf(objects);
+                }
+                mid
+            }
+        };
+        let right = objects.split_off(mid);
+        let (left, right) = if parallel && objects.len() + right.len() >= PARALLEL_MIN_OBJECTS {
+            join(
+                || Self::build(objects, split, true),
+                || Self::build(right, split, true),
+            )
+        } else {
+            (
+                Self::build(objects, split, parallel),
+                Self::build(right, split, parallel),
+            )
+        };
+        let (left, right) = (Box::new(left), Box::new(right));
+        Node {
+            bbox: surrounding_box(&left.bbox, &right.bbox),
+            contents: Contents::Split(left, right),
+        }
+    }
+
+    fn leaf(objects: Vec<Entry>) -> Self {
+        Node {
+            bbox: bounds(&objects),
+            contents: Contents::Leaf(objects.into_iter().map(|(_, id, obj)| (id, obj)).collect()),
+        }
+    }
+
+    // Returns how many objects the leaves below this node hold.
+    fn gather_stats(&self, depth: usize, stats: &mut BvhStats) -> usize {
+        stats.node_count += 1;
+        stats.max_depth = stats.max_depth.max(depth);
+        match &self.contents {
+            Contents::Leaf(objects) => {
+                stats.leaf_count += 1;
+                objects.len()
+            }
+            Contents::Split(left, right) => {
+                left.gather_stats(depth + 1, stats) + right.gather_stats(depth + 1, stats)
+            }
+        }
+    }
+
+    fn traverse(
+        &self,
+        r: &Ray,
+        t_min: Float,
+        t_max: Float,
+        visited: &mut usize,
+    ) -> Option<HitRecord> {
+        *visited += 1;
+        if !self.bbox.hit(r, t_min, t_max) {
+            return None;
+        }
+        match &self.contents {
+            Contents::Leaf(objects) => {
+                let mut closest = None;
+                for (obj_id, obj) in objects {
+                    let t_max = closest.as_ref().map_or(t_max, |rec: &HitRecord| rec.t);
+                    if let Some(rec) = obj.hit(r, t_min, t_max) {
+                        closest = Some(HitRecord {
+                            obj_id: *obj_id,
+                            ..rec
+                        });
+                    }
+                }
+                closest
+            }
+            Contents::Split(left, right) => {
+                let left_hit = left.traverse(r, t_min, t_max, visited);
+                // anything on the right has to be closer than what the left found
+                let t_max = left_hit.as_ref().map_or(t_max, |rec| rec.t);
+                right.traverse(r, t_min, t_max, visited).or(left_hit)
+            }
+        }
+    }
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        self.traverse(r, t_min, t_max, &mut 0)
+    }
+
+    // None if any object is unbounded, like a `HitList` holding it.
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        match &self.tree {
+            Some(tree) if self.unbounded.is_empty() => Some(tree.bbox),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BvhStats {
+    // every node, leaves included
+    pub node_count: usize,
+    pub leaf_count: usize,
+    // edges from the root to the deepest leaf
+    pub max_depth: usize,
+    // objects per leaf
+    pub avg_leaf_size: Float,
+}
+
+// A `BvhNode` that keeps count of its traversals, from `BvhNode::with_traversal_stats`.
+pub struct CountingBvh {
+    bvh: BvhNode,
+    rays: AtomicUsize,
+    nodes_visited: AtomicUsize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraversalStats {
+    pub rays: usize,
+    pub nodes_visited: usize,
+}
+
+impl TraversalStats {
+    pub fn avg_nodes_per_ray(&self) -> Float {
+        self.nodes_visited as Float / self.rays.max(1) as Float
+    }
+}
+
+impl CountingBvh {
+    pub fn traversal_stats(&self) -> TraversalStats {
+        TraversalStats {
+            rays: self.rays.load(Ordering::Relaxed),
+            nodes_visited: self.nodes_visited.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn into_inner(self) -> BvhNode {
+        self.bvh
+    }
+}
+
+impl Hittable for CountingBvh {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let mut visited = 0;
+        let rec = self.bvh.traverse(r, t_min, t_max, &mut visited);
+        self.rays.fetch_add(1, Ordering::Relaxed);
+        self.nodes_visited.fetch_add(visited, Ordering::Relaxed);
+        rec
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        self.bvh.bounding_box(time0, time1)
+    }
+}
+
+#[cfg(feature = "rayon")]
+use rayon::join;
+
+// Only `with_split_parallel` asks for a parallel build, and it needs rayon.
+#[cfg(not(feature = "rayon"))]
+fn join<A, B>(a: impl FnOnce() -> A, b: impl FnOnce() -> B) -> (A, B) {
+    (a(), b())
+}
+
+// An object waiting to be placed in the tree, with its bounding box and id.
+type Entry = (Aabb, usize, Box<dyn Hittable>);
+
+fn bounds(objects: &[Entry]) -> Aabb {
+    objects[1..]
+        .iter()
+        .fold(objects[0].0, |bbox, entry| surrounding_box(&bbox, &entry.0))
+}
+
+fn sort_by_centroid(objects: &mut [Entry], axis: usize) {
+    objects.sort_by(|a, b| a.0.centroid()[axis].total_cmp(&b.0.centroid()[axis]));
+}
+
+// Sorts `objects` along the axis their centroids spread the most, returning the middle.
+fn median_split(objects: &mut [Entry]) -> usize {
+    let first = objects[0].0.centroid();
+    let (min, max) = objects
+        .iter()
+        .fold((first, first), |(min, max), (bbox, _, _)| {
+            (min.min(bbox.centroid()), max.max(bbox.centroid()))
+        });
+    let extent = max - min;
+    let axis = (0..3)
+        .max_by(|&a, &b| extent[a].total_cmp(&extent[b]))
+        .unwrap();
+    sort_by_centroid(objects, axis);
+    objects.len() / 2
+}
+
+// Sorts `objects` along the best axis and returns the split index minimizing
+// area_left * count_left + area_right * count_right, along with that minimum.
+fn sah_split(objects: &mut [Entry]) -> (usize, Float) {
+    let n = objects.len();
+    let mut best: Option<(Float, usize, usize)> = None;
+    for axis in 0..3 {
+        sort_by_centroid(objects, axis);
+
+        // right_areas[i] bounds objects[i..]
+        let mut right_areas = vec![0.0; n];
+        let mut bbox = objects[n - 1].0;
+        for i in (1..n).rev() {
+            bbox = surrounding_box(&bbox, &objects[i].0);
+            right_areas[i] = bbox.surface_area();
+        }
+
+        let mut left_box = objects[0].0;
+        for mid in 1..n {
+            left_box = surrounding_box(&left_box, &objects[mid - 1].0);
+            let cost =
+                left_box.surface_area() * mid as Float + right_areas[mid] * (n - mid) as Float;
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, mid));
+            }
+        }
+    }
+
+    let (cost, axis, mid) = best.unwrap();
+    sort_by_centroid(objects, axis);
+    (mid, cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plane::Plane;
+    use crate::test_util::{mat, sphere};
+    use crate::util::{random_bounded, seed_rng};
+    use crate::vec3::{Point3, Vec3};
+    use crate::HitList;
+
+    fn random_point(min: Float, max: Float) -> Point3 {
+        Point3::new(
+            random_bounded(min, max),
+            random_bounded(min, max),
+            random_bounded(min, max),
+        )
+    }
+
+    #[test]
+    fn test_bvh_matches_hit_list() {
+        seed_rng(1);
+        let centers: Vec<Point3> = (0..50).map(|_| random_point(-5.0, 5.0)).collect();
+        let mut list = HitList::new();
+        for &c in &centers {
+            list.add(sphere(c, 0.5));
+        }
+        for split in [SplitMethod::Median, SplitMethod::Sah] {
+            let objects = centers.iter().map(|&c| sphere(c, 0.5)).collect();
+            let bvh = BvhNode::with_split(objects, 0.0, 1.0, split);
+            assert_eq!(bvh.bounding_box(0.0, 1.0), list.bounding_box(0.0, 1.0));
+            for _ in 0..200 {
+                let r = Ray::new(random_point(-8.0, 8.0), Vec3::new_random_unit_vector());
+                let expected = list
+                    .hit(&r, 0.001, Float::INFINITY)
+                    .map(|rec| (rec.t, rec.obj_id));
+                assert_eq!(
+                    bvh.hit(&r, 0.001, Float::INFINITY)
+                        .map(|rec| (rec.t, rec.obj_id)),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_bvh_with_plane_matches_hit_list() {
+        seed_rng(4);
+        let centers: Vec<Point3> = (0..30).map(|_| random_point(-5.0, 5.0)).collect();
+        let build = || {
+            let mut objects: Vec<Box<dyn Hittable>> =
+                centers.iter().map(|&c| sphere(c, 0.5)).collect();
+            // a ground plane in the middle of the list, so its id isn't the last
+            objects.insert(
+                12,
+                Box::new(Plane::new(
+                    Point3::new(0.0, -1.0, 0.0),
+                    Vec3::new(0.0, 1.0, 0.0),
+                    mat(),
+                )),
+            );
+            objects
+        };
+        let list = HitList::from_vec(build());
+        let bvh = BvhNode::new(build(), 0.0, 1.0);
+        assert!(bvh.bounding_box(0.0, 1.0).is_none());
+
+        let mut plane_hits = 0;
+        for _ in 0..500 {
+            let r = Ray::new(random_point(-8.0, 8.0), Vec3::new_random_unit_vector());
+            let expected = list
+                .hit(&r, 0.001, Float::INFINITY)
+                .map(|rec| (rec.t, rec.obj_id));
+            if expected.is_some_and(|(_, id)| id == 12) {
+                plane_hits += 1;
+            }
+            assert_eq!(
+                bvh.hit(&r, 0.001, Float::INFINITY)
+                    .map(|rec| (rec.t, rec.obj_id)),
+                expected
+            );
+        }
+        assert!(plane_hits > 50, "only {plane_hits} rays hit the plane");
+
+        // only unbounded objects: no tree at all
+        let planes = BvhNode::new(vec![build().remove(12)], 0.0, 1.0);
+        let down = Ray::new(Point3::new(0.0, 3.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(planes.hit(&down, 0.001, Float::INFINITY).unwrap().t, 4.0);
+        assert_eq!(planes.stats().node_count, 0);
+    }
+
+    #[test]
+    fn test_stats_describe_tree_shape() {
+        // five in a row: split 2 | 3, then the 3 splits 1 | 2
+        let objects = (0..5)
+            .map(|i| sphere(Point3::new(3.0 * i as Float, 0.0, 0.0), 1.0))
+            .collect();
+        let bvh = BvhNode::new(objects, 0.0, 1.0);
+        assert_eq!(
+            bvh.stats(),
+            BvhStats {
+                node_count: 9,
+                leaf_count: 5,
+                max_depth: 3,
+                avg_leaf_size: 1.0,
+            }
+        );
+
+        // SAH keeps two clumps of nested spheres whole, as there's nothing to gain by
+        // splitting them
+        let clumps = || {
+            [0.0, 20.0]
+                .into_iter()
+                .flat_map(|x| [0.5, 0.6, 0.7].map(|r| sphere(Point3::new(x, 0.0, 0.0), r)))
+                .collect::<Vec<_>>()
+        };
+        let sah = BvhNode::with_split(clumps(), 0.0, 1.0, SplitMethod::Sah);
+        assert_eq!(
+            sah.stats(),
+            BvhStats {
+                node_count: 3,
+                leaf_count: 2,
+                max_depth: 1,
+                avg_leaf_size: 3.0,
+            }
+        );
+        let list = HitList::from_vec(clumps());
+        for x in [0.0, 20.0] {
+            let r = Ray::new(Point3::new(x, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+            let expected = list
+                .hit(&r, 0.001, Float::INFINITY)
+                .map(|rec| (rec.t, rec.obj_id));
+            assert_eq!(
+                sah.hit(&r, 0.001, Float::INFINITY)
+                    .map(|rec| (rec.t, rec.obj_id)),
+                expected
+            );
+        }
+
+        let single = BvhNode::new(vec![sphere(Point3::new(0.0, 0.0, 0.0), 1.0)], 0.0, 1.0);
+        assert_eq!(
+            (single.stats().node_count, single.stats().max_depth),
+            (1, 0)
+        );
+
+        // a ray down the row tests the root, the left half and its two leaves, then
+        // the right half's box (beyond the first hit); one beside the row only the root
+        let counting = bvh.with_traversal_stats();
+        let along = Ray::new(Point3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(counting.hit(&along, 0.001, Float::INFINITY).is_some());
+        let beside = Ray::new(Point3::new(-5.0, 5.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(counting.hit(&beside, 0.001, Float::INFINITY).is_none());
+        let stats = counting.traversal_stats();
+        assert_eq!((stats.rays, stats.nodes_visited), (2, 5 + 1));
+        assert_eq!(stats.avg_nodes_per_ray(), 3.0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_build_matches_serial() {
+        seed_rng(3);
+        let centers: Vec<Point3> = (0..3000).map(|_| random_point(-20.0, 20.0)).collect();
+        for split in [SplitMethod::Median, SplitMethod::Sah] {
+            let build = |parallel: bool| {
+                let objects = centers.iter().map(|&c| sphere(c, 0.3)).collect();
+                if parallel {
+                    BvhNode::with_split_parallel(objects, 0.0, 1.0, split)
+                } else {
+                    BvhNode::with_split(objects, 0.0, 1.0, split)
+                }
+            };
+            let (serial, parallel) = (build(false), build(true));
+            for _ in 0..500 {
+                let r = Ray::new(random_point(-25.0, 25.0), Vec3::new_random_unit_vector());
+                let (mut serial_visits, mut parallel_visits) = (0, 0);
+                let expected = serial
+                    .traverse(&r, 0.001, Float::INFINITY, &mut serial_visits)
+                    .map(|rec| (rec.t, rec.obj_id));
+                let found = parallel
+                    .traverse(&r, 0.001, Float::INFINITY, &mut parallel_visits)
+                    .map(|rec| (rec.t, rec.obj_id));
+                assert_eq!(found, expected);
+                assert_eq!(parallel_visits, serial_visits);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sah_visits_fewer_nodes_on_clustered_scene() {
+        // a dense clump of small spheres and a sparse scattering of big ones far away
+        seed_rng(2);
+        let mut spheres: Vec<(Point3, Float)> =
+            (0..200).map(|_| (random_point(-1.0, 1.0), 0.05)).collect();
+        spheres.extend((0..20).map(|i| {
+            let x = 10.0 + 5.0 * i as Float;
+            (Point3::new(x, random_bounded(-5.0, 5.0), 0.0), 2.0)
+        }));
+        let build = |split| {
+            let objects = spheres.iter().map(|&(c, r)| sphere(c, r)).collect();
+            BvhNode::with_split(objects, 0.0, 1.0, split)
+        };
+        let median = build(SplitMethod::Median);
+        let sah = build(SplitMethod::Sah);
+
+        let (mut median_visits, mut sah_visits) = (0, 0);
+        for _ in 0..2000 {
+            let r = Ray::new(
+                Point3::new(random_bounded(-5.0, 110.0), 0.0, 50.0),
+                Vec3::new(0.0, 0.0, -1.0) + 0.1 * Vec3::new_random_unit_vector(),
+            );
+            let expected = median.traverse(&r, 0.001, Float::INFINITY, &mut median_visits);
+            let found = sah.traverse(&r, 0.001, Float::INFINITY, &mut sah_visits);
+            assert_eq!(found.map(|rec| rec.t), expected.map(|rec| rec.t));
+        }
+        assert!(
+            sah_visits < median_visits,
+            "{sah_visits} nodes visited with SAH, {median_visits} with median splits"
+        );
+    }
+}
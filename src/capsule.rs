@@ -0,0 +1,188 @@
+use crate::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{hit_sphere, HitRecord, Hittable, Ray};
+
+// A cylinder from `a` to `b` with hemispherical caps: every point within `radius` of the segment.
+pub struct Capsule {
+    pub a: Point3,
+    pub b: Point3,
+    pub radius: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Capsule {
+    pub fn new(a: Point3, b: Point3, radius: Float, mat_ptr: Arc<dyn Material>) -> Self {
+        Capsule {
+            a,
+            b,
+            radius,
+            mat_ptr,
+        }
+    }
+
+    // Position of `p` along the segment, 0 at `a` and 1 at `b`.
+    fn axis_param(&self, p: Point3) -> Float {
+        let ba = self.b - self.a;
+        dot(p - self.a, ba) / ba.length_squared()
+    }
+
+    // Roots of |o + t d|^2 = radius^2, nearest first.
+    fn roots(&self, o: Vec3, d: Vec3) -> Option<[Float; 2]> {
+        let a = d.length_squared();
+        if a < 1e-12 {
+            return None;
+        }
+        let half_b = dot(o, d);
+        let c = o.length_squared() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+        Some([(-half_b - sqrtd) / a, (-half_b + sqrtd) / a])
+    }
+}
+
+impl Hittable for Capsule {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        // with no length there's no axis, and what's left is a sphere
+        if self.a == self.b {
+            return hit_sphere(self.a, self.radius, &self.mat_ptr, r, t_min, t_max);
+        }
+        let ba = self.b - self.a;
+        let axis = ba.unit_vector();
+        let oa = r.orig - self.a;
+
+        // Candidate roots: the infinite cylinder, limited to the segment, and the two end spheres,
+        // limited to the outside of the segment.
+        let mut candidates = Vec::with_capacity(6);
+        let perp = |v: Vec3| v - dot(v, axis) * axis;
+        if let Some(roots) = self.roots(perp(oa), perp(r.dir)) {
+            candidates.extend(roots.into_iter().filter(|&t| {
+                let h = self.axis_param(r.at(t));
+                (0.0..=1.0).contains(&h)
+            }));
+        }
+        if let Some(roots) = self.roots(oa, r.dir) {
+            candidates.extend(
+                roots
+                    .into_iter()
+                    .filter(|&t| self.axis_param(r.at(t)) < 0.0),
+            );
+        }
+        if let Some(roots) = self.roots(r.orig - self.b, r.dir) {
+            candidates.extend(
+                roots
+                    .into_iter()
+                    .filter(|&t| self.axis_param(r.at(t)) > 1.0),
+            );
+        }
+
+        let t = candidates
+            .into_iter()
+            .filter(|&t| t_min <= t && t <= t_max)
+            .min_by(|x, y| x.total_cmp(y))?;
+
+        let p = r.at(t);
+        // the normal points away from the nearest point on the segment
+        let h = self.axis_param(p).clamp(0.0, 1.0);
+        let outward_normal = (p - (self.a + h * ba)) / self.radius;
+        let onb = Onb::build_from_w(axis);
+        let phi = Float::atan2(dot(outward_normal, onb.v), dot(outward_normal, onb.u)) + PI;
+
+        let hr = HitRecord {
+            p,
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: phi / (2.0 * PI),
+            v: h,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        let radius = Vec3::new(self.radius, self.radius, self.radius).map(Float::abs);
+        Some(Aabb::new(
+            self.a.min(self.b) - radius,
+            self.a.max(self.b) + radius,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::tolerance;
+    use crate::vec3::COLOR_WHITE;
+
+    // upright, from y = 0 to y = 2
+    fn capsule() -> Capsule {
+        Capsule::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 2.0, 0.0),
+            0.5,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_hit_body() {
+        let r = Ray::new(Point3::new(3.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = capsule().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 2.5).abs() < tolerance(1e-12));
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < tolerance(1e-12));
+        assert!(rec.front_face);
+        assert!((rec.v - 0.5).abs() < tolerance(1e-12));
+    }
+
+    #[test]
+    fn test_hit_cap() {
+        let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let rec = capsule().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 2.5).abs() < tolerance(1e-12));
+        assert!((rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < tolerance(1e-12));
+
+        // off-axis on the bottom cap, the normal points away from the endpoint
+        let r = Ray::new(Point3::new(0.3, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let rec = capsule().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.p - Point3::new(0.3, -0.4, 0.0)).length() < tolerance(1e-12));
+        assert!((rec.normal - Vec3::new(0.6, -0.8, 0.0)).length() < tolerance(1e-12));
+
+        // from inside, the far side is hit
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let rec = capsule().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 1.5).abs() < tolerance(1e-12));
+        assert!(!rec.front_face);
+    }
+
+    #[test]
+    fn test_zero_length_is_a_sphere() {
+        let point = Point3::new(0.0, 1.0, 0.0);
+        let ball = Capsule::new(point, point, 0.5, Arc::new(Lambertian::new(COLOR_WHITE)));
+        let r = Ray::new(Point3::new(3.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = ball.hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 2.5).abs() < tolerance(1e-12));
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < tolerance(1e-12));
+    }
+
+    #[test]
+    fn test_miss_outside_radius() {
+        let r = Ray::new(Point3::new(0.6, 1.0, 3.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(capsule().hit(&r, 0.001, Float::INFINITY).is_none());
+
+        let bbox = capsule().bounding_box(0.0, 1.0).unwrap();
+        assert_eq!(bbox.minimum, Point3::new(-0.5, -0.5, -0.5));
+        assert_eq!(bbox.maximum, Point3::new(0.5, 2.5, 0.5));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// A finite cone, opening along `axis` from its apex out to a base `height` away.
+pub struct Cone {
+    pub apex: Point3,
+    pub axis: Vec3,
+    // angle between the axis and the slanted surface
+    pub half_angle: Float,
+    pub height: Float,
+    // whether the base is closed with a disk
+    pub capped: bool,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Cone {
+    pub fn new(
+        apex: Point3,
+        axis: Vec3,
+        half_angle: Float,
+        height: Float,
+        capped: bool,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        Cone {
+            apex,
+            axis: axis.unit_vector(),
+            half_angle,
+            height,
+            capped,
+            mat_ptr,
+        }
+    }
+
+    fn base_radius(&self) -> Float {
+        self.height * self.half_angle.tan()
+    }
+
+    // Angle around the axis of a point offset `q` from it, in [0, 1).
+    fn angle(&self, q: Vec3) -> Float {
+        let a = if self.axis.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let s = self.axis.cross(a).unit_vector();
+        let t = self.axis.cross(s);
+        (Float::atan2(dot(q, t), dot(q, s)) + PI) / (2.0 * PI)
+    }
+
+    // The nearest hit on the slanted surface, as (t, outward normal, u, v).
+    fn hit_side(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<(Float, Vec3, Float, Float)> {
+        // Points q on the double cone satisfy dot(q, axis)^2 = cos^2 * dot(q, q).
+        let cos2 = self.half_angle.cos().powi(2);
+        let oc = r.orig - self.apex;
+        let dv = dot(r.dir, self.axis);
+        let ov = dot(oc, self.axis);
+
+        let a = dv * dv - cos2 * dot(r.dir, r.dir);
+        let half_b = dv * ov - cos2 * dot(oc, r.dir);
+        let c = ov * ov - cos2 * dot(oc, oc);
+        let roots = if a.abs() < 1e-12 {
+            // the ray runs parallel to the slant, crossing the surface at most once
+            if half_b.abs() < 1e-12 {
+                return None;
+            }
+            let root = -c / (2.0 * half_b);
+            [root, root]
+        } else {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let sqrtd = discriminant.sqrt();
+            let (t0, t1) = ((-half_b - sqrtd) / a, (-half_b + sqrtd) / a);
+            [t0.min(t1), t0.max(t1)]
+        };
+
+        for root in roots {
+            if root < t_min || t_max < root {
+                continue;
+            }
+            let q = r.at(root) - self.apex;
+            // rejects the mirrored nappe behind the apex as well as anything past the base
+            let h = dot(q, self.axis);
+            if (0.0..=self.height).contains(&h) {
+                let radial = (q - h * self.axis).normalized();
+                let outward_normal =
+                    self.half_angle.cos() * radial - self.half_angle.sin() * self.axis;
+                return Some((root, outward_normal, self.angle(q), h / self.height));
+            }
+        }
+        None
+    }
+
+    // The hit on the base disk, as (t, outward normal, u, v).
+    fn hit_cap(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<(Float, Vec3, Float, Float)> {
+        let denom = dot(r.dir, self.axis);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+
+        let t = (self.height - dot(r.orig - self.apex, self.axis)) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+        let q = r.at(t) - (self.apex + self.height * self.axis);
+        let dist = q.length();
+        let radius = self.base_radius();
+        if dist > radius {
+            return None;
+        }
+        Some((t, self.axis, self.angle(q), dist / radius))
+    }
+}
+
+impl Hittable for Cone {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let side = self.hit_side(r, t_min, t_max);
+        let cap = if self.capped {
+            self.hit_cap(r, t_min, side.map_or(t_max, |s| s.0))
+        } else {
+            None
+        };
+        let (t, outward_normal, u, v) = cap.or(side)?;
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u,
+            v,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // The base disk extends radius * sin(angle between the axis and each world axis).
+        let radius = self.base_radius();
+        let extent = Vec3::new(
+            radius * (1.0 - self.axis.x() * self.axis.x()).max(0.0).sqrt(),
+            radius * (1.0 - self.axis.y() * self.axis.y()).max(0.0).sqrt(),
+            radius * (1.0 - self.axis.z() * self.axis.z()).max(0.0).sqrt(),
+        );
+        let base = self.apex + self.height * self.axis;
+        Some(Aabb::new(
+            (base - extent).min(self.apex),
+            (base + extent).max(self.apex),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::tolerance;
+    use crate::vec3::COLOR_WHITE;
+
+    // a 45 degree cone hanging from an apex at y = 2 down to a base of radius 2 on the ground
+    fn cone(capped: bool) -> Cone {
+        Cone::new(
+            Point3::new(0.0, 2.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            PI / 4.0,
+            2.0,
+            capped,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_side_hit() {
+        let r = Ray::new(Point3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = cone(true).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 4.0).abs() < tolerance(1e-9));
+        let slanted = Vec3::new(1.0, 1.0, 0.0).unit_vector();
+        assert!((rec.normal - slanted).length() < tolerance(1e-9));
+        assert!(rec.front_face);
+        assert!((rec.v - 0.5).abs() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_cap_hit() {
+        let r = Ray::new(Point3::new(0.5, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let rec = cone(true).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 1.0).abs() < tolerance(1e-9));
+        assert_eq!(rec.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert!(rec.front_face);
+    }
+
+    #[test]
+    fn test_ray_up_the_open_base_hits_inside() {
+        // without a cap, the ray enters through the base and meets the slant from within
+        let r = Ray::new(Point3::new(0.5, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let rec = cone(false).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 2.5).abs() < tolerance(1e-9));
+        assert!(!rec.front_face);
+        assert!(rec.normal.y() < 0.0);
+    }
+
+    #[test]
+    fn test_ray_above_apex_misses() {
+        // the mirrored half of the double cone is not part of the shape
+        let r = Ray::new(Point3::new(5.0, 3.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert!(cone(true).hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = cone(true).bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(-2.0, 0.0, -2.0)).length() < tolerance(1e-9));
+        assert!((bbox.maximum - Point3::new(2.0, 2.0, 2.0)).length() < tolerance(1e-9));
+    }
+}
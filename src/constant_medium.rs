@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::{Isotropic, Material};
+use crate::util::random_double;
+use crate::vec3::{Color, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray, INFINITY};
+
+// A volume of constant density (smoke, fog, mist) filling a closed `boundary`.
+// Rays passing through it may scatter at any point inside.
+pub struct ConstantMedium {
+    boundary: Box<dyn Hittable>,
+    neg_inv_density: Float,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Box<dyn Hittable>, density: Float, albedo: Color) -> Self {
+        Self::with_phase_function(boundary, density, Arc::new(Isotropic::new(albedo)))
+    }
+
+    pub fn with_phase_function(
+        boundary: Box<dyn Hittable>,
+        density: Float,
+        phase_function: Arc<dyn Material>,
+    ) -> Self {
+        ConstantMedium {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        // Find where the ray enters and leaves the boundary, even if that's behind the ray.
+        let rec1 = self.boundary.hit(r, -INFINITY, INFINITY)?;
+        let rec2 = self.boundary.hit(r, rec1.t + 0.0001, INFINITY)?;
+
+        let t1 = rec1.t.max(t_min).max(0.0);
+        let t2 = rec2.t.min(t_max);
+        if t1 >= t2 {
+            return None;
+        }
+
+        let ray_length = r.dir.length();
+        let distance_inside_boundary = (t2 - t1) * ray_length;
+        let hit_distance = self.neg_inv_density * random_double().ln();
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = t1 + hit_distance / ray_length;
+        Some(HitRecord {
+            p: r.at(t),
+            // arbitrary: a scattering point inside a volume has no surface
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: 0.0,
+            v: 0.0,
+            front_face: true,
+            mat_ptr: Arc::clone(&self.phase_function),
+            obj_id: 0,
+        })
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        self.boundary.bounding_box(time0, time1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::rect::BoxPrim;
+    use crate::vec3::{Point3, COLOR_WHITE};
+
+    fn medium(density: Float) -> ConstantMedium {
+        let boundary = BoxPrim::new(
+            Point3::new(-5.0, -5.0, -5.0),
+            Point3::new(5.0, 5.0, 5.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        ConstantMedium::new(Box::new(boundary), density, COLOR_WHITE)
+    }
+
+    fn average_scatter_distance(medium: &ConstantMedium) -> Float {
+        let r = Ray::new(Point3::new(0.0, 0.0, -10.0), Vec3::new(0.0, 0.0, 1.0));
+        let hits: Vec<Float> = (0..2000)
+            .filter_map(|_| medium.hit(&r, 0.001, INFINITY))
+            .map(|rec| {
+                assert!((5.0..=15.0).contains(&rec.t));
+                rec.t - 5.0
+            })
+            .collect();
+        hits.iter().sum::<Float>() / hits.len() as Float
+    }
+
+    #[test]
+    fn test_denser_medium_scatters_sooner() {
+        let dense = average_scatter_distance(&medium(10.0));
+        let thin = average_scatter_distance(&medium(0.1));
+        assert!(dense < 0.2, "dense medium scattered after {dense}");
+        assert!(thin > 10.0 * dense, "thin medium scattered after {thin}");
+    }
+
+    #[test]
+    fn test_ray_can_start_inside_medium() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let rec = medium(1000.0).hit(&r, 0.001, INFINITY).unwrap();
+        assert!(rec.t < 0.1);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::aabb::{surrounding_box, Aabb};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray, INFINITY};
+
+// Constructive solid geometry: boolean combinations of closed solids.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    // the left solid with the right one carved out of it
+    Difference,
+}
+
+impl CsgOp {
+    fn inside(self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOp::Union => in_left || in_right,
+            CsgOp::Intersection => in_left && in_right,
+            CsgOp::Difference => in_left && !in_right,
+        }
+    }
+}
+
+pub struct Csg {
+    pub op: CsgOp,
+    pub left: Box<dyn Hittable>,
+    pub right: Box<dyn Hittable>,
+}
+
+impl Csg {
+    pub fn new(op: CsgOp, left: Box<dyn Hittable>, right: Box<dyn Hittable>) -> Self {
+        Csg { op, left, right }
+    }
+}
+
+// Every surface crossing of a closed solid along the whole line of `r`, nearest first.
+// Front-face hits enter the solid and back-face hits leave it, so consecutive crossings
+// form the solid's (entry, exit) intervals.
+pub fn interval_hit(solid: &dyn Hittable, r: &Ray) -> Vec<HitRecord> {
+    // guards against getting stuck on surfaces that don't advance t
+    const MAX_CROSSINGS: usize = 64;
+
+    let mut crossings = Vec::new();
+    let mut t = -INFINITY;
+    while crossings.len() < MAX_CROSSINGS {
+        match solid.hit(r, t, INFINITY) {
+            Some(rec) => {
+                // a relative step well above rounding error: ~1e-9 for f64, ~1e-5 for f32
+                t = rec.t + 0.1 * Float::EPSILON.sqrt() * rec.t.abs().max(1.0);
+                crossings.push(rec);
+            }
+            None => break,
+        }
+    }
+    crossings
+}
+
+impl Hittable for Csg {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let mut events: Vec<(HitRecord, bool)> = interval_hit(self.left.as_ref(), r)
+            .into_iter()
+            .map(|rec| (rec, true))
+            .chain(
+                interval_hit(self.right.as_ref(), r)
+                    .into_iter()
+                    .map(|rec| (rec, false)),
+            )
+            .collect();
+        events.sort_by(|a, b| a.0.t.total_cmp(&b.0.t));
+
+        // Walk along the ray; the combined surface is wherever the combined inside-ness changes.
+        let (mut in_left, mut in_right) = (false, false);
+        for (rec, from_left) in events {
+            let was_inside = self.op.inside(in_left, in_right);
+            if from_left {
+                in_left = rec.front_face;
+            } else {
+                in_right = rec.front_face;
+            }
+            let is_inside = self.op.inside(in_left, in_right);
+            if was_inside == is_inside || rec.t < t_min || t_max < rec.t {
+                continue;
+            }
+
+            // Recover the child's outward normal; carved-out surfaces face the other way.
+            let mut outward_normal = if rec.front_face {
+                rec.normal
+            } else {
+                -rec.normal
+            };
+            if self.op == CsgOp::Difference && !from_left {
+                outward_normal = -outward_normal;
+            }
+            return Some(rec.with_face_normal(r, outward_normal));
+        }
+        None
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        let left = self.left.bounding_box(time0, time1);
+        let right = self.right.bounding_box(time0, time1);
+        match self.op {
+            CsgOp::Union => Some(surrounding_box(&left?, &right?)),
+            CsgOp::Difference => left,
+            CsgOp::Intersection => match (left, right) {
+                (Some(l), Some(r)) => Some(Aabb::new(
+                    l.minimum.max(r.minimum),
+                    l.maximum.min(r.maximum),
+                )),
+                (bbox, None) | (None, bbox) => bbox,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sphere;
+    use crate::tolerance;
+    use crate::vec3::{Point3, Vec3};
+
+    #[test]
+    fn test_interval_hit() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let crossings = interval_hit(sphere(Point3::new(0.0, 0.0, 0.0), 1.0).as_ref(), &r);
+        assert_eq!(crossings.len(), 2);
+        assert_eq!((crossings[0].t, crossings[1].t), (4.0, 6.0));
+        assert!(crossings[0].front_face && !crossings[1].front_face);
+    }
+
+    #[test]
+    fn test_difference_makes_a_dent() {
+        let dented = Csg::new(
+            CsgOp::Difference,
+            sphere(Point3::new(0.0, 0.0, 0.0), 1.0),
+            sphere(Point3::new(0.0, 0.0, 1.0), 0.6),
+        );
+
+        // straight into the dent: the carved-out sphere's far side, facing the ray
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = dented.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.t - 4.6).abs() < tolerance(1e-9));
+        assert!(rec.front_face);
+        assert!((rec.normal - Vec3::new(0.0, 0.0, 1.0)).length() < tolerance(1e-9));
+
+        // beside the dent, the original surface remains
+        let r = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let rec = dented.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.t - 4.0).abs() < tolerance(1e-9));
+        assert!((rec.normal - Vec3::new(0.0, 0.0, -1.0)).length() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_intersection_is_a_lens() {
+        let lens = Csg::new(
+            CsgOp::Intersection,
+            sphere(Point3::new(-0.5, 0.0, 0.0), 1.0),
+            sphere(Point3::new(0.5, 0.0, 0.0), 1.0),
+        );
+
+        // thin along x: the edge of the left sphere
+        let r = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = lens.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.p.x() - 0.5).abs() < tolerance(1e-9));
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < tolerance(1e-9));
+
+        // tall along y
+        let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let rec = lens.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.p.y() - Float::sqrt(0.75)).abs() < tolerance(1e-9));
+
+        // inside the right sphere but outside the left one
+        let r = Ray::new(Point3::new(1.2, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(lens.hit(&r, 0.001, INFINITY).is_none());
+
+        let bbox = lens.bounding_box(0.0, 1.0).unwrap();
+        assert_eq!(bbox.minimum, Point3::new(-0.5, -1.0, -1.0));
+        assert_eq!(bbox.maximum, Point3::new(0.5, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_union() {
+        let pair = Csg::new(
+            CsgOp::Union,
+            sphere(Point3::new(-0.5, 0.0, 0.0), 1.0),
+            sphere(Point3::new(0.5, 0.0, 0.0), 1.0),
+        );
+        // from inside the overlap, the first surface hit is the right sphere's far side
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let rec = pair.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.t - 1.5).abs() < tolerance(1e-9));
+        assert!(!rec.front_face);
+    }
+}
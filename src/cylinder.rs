@@ -0,0 +1,215 @@
+use crate::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// A finite cylinder, extending `height` along `axis` from the center of its base.
+pub struct Cylinder {
+    pub base: Point3,
+    pub axis: Vec3,
+    pub radius: Float,
+    pub height: Float,
+    // whether the two ends are closed with disks
+    pub capped: bool,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Cylinder {
+    pub fn new(
+        base: Point3,
+        axis: Vec3,
+        radius: Float,
+        height: Float,
+        capped: bool,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        Cylinder {
+            base,
+            axis: axis.unit_vector(),
+            radius,
+            height,
+            capped,
+            mat_ptr,
+        }
+    }
+
+    // Angle around the axis of a point offset `q` from it, in [0, 1).
+    fn angle(&self, q: Vec3) -> Float {
+        let a = if self.axis.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let s = self.axis.cross(a).unit_vector();
+        let t = self.axis.cross(s);
+        (Float::atan2(dot(q, t), dot(q, s)) + PI) / (2.0 * PI)
+    }
+
+    // The nearest hit on the lateral surface, as (t, outward normal, u, v).
+    fn hit_side(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<(Float, Vec3, Float, Float)> {
+        // Solve the sphere-like quadratic for the components perpendicular to the axis.
+        let oc = r.orig - self.base;
+        let d_perp = r.dir - dot(r.dir, self.axis) * self.axis;
+        let oc_perp = oc - dot(oc, self.axis) * self.axis;
+
+        let a = dot(d_perp, d_perp);
+        if a < 1e-12 {
+            // the ray runs parallel to the axis, so it can only hit the caps
+            return None;
+        }
+        let half_b = dot(oc_perp, d_perp);
+        let c = dot(oc_perp, oc_perp) - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+
+        for root in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+            if root < t_min || t_max < root {
+                continue;
+            }
+            let q = r.at(root) - self.base;
+            let h = dot(q, self.axis);
+            if (0.0..=self.height).contains(&h) {
+                let outward_normal = (q - h * self.axis) / self.radius;
+                return Some((root, outward_normal, self.angle(q), h / self.height));
+            }
+        }
+        None
+    }
+
+    // The nearest hit on either end cap, as (t, outward normal, u, v).
+    fn hit_caps(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<(Float, Vec3, Float, Float)> {
+        let denom = dot(r.dir, self.axis);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+
+        let oc = r.orig - self.base;
+        let mut closest: Option<(Float, Vec3, Float, Float)> = None;
+        for (h, outward_normal) in [(0.0, -self.axis), (self.height, self.axis)] {
+            let t = (h - dot(oc, self.axis)) / denom;
+            let t_limit = closest.map_or(t_max, |c| c.0);
+            if t < t_min || t_limit < t {
+                continue;
+            }
+            let q = r.at(t) - (self.base + h * self.axis);
+            let dist = q.length();
+            if dist <= self.radius {
+                closest = Some((t, outward_normal, self.angle(q), dist / self.radius));
+            }
+        }
+        closest
+    }
+}
+
+impl Hittable for Cylinder {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let side = self.hit_side(r, t_min, t_max);
+        let cap = if self.capped {
+            self.hit_caps(r, t_min, side.map_or(t_max, |s| s.0))
+        } else {
+            None
+        };
+        let (t, outward_normal, u, v) = cap.or(side)?;
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u,
+            v,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // The end disks extend radius * sin(angle between the axis and each world axis).
+        let extent = Vec3::new(
+            self.radius * (1.0 - self.axis.x() * self.axis.x()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.axis.y() * self.axis.y()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.axis.z() * self.axis.z()).max(0.0).sqrt(),
+        );
+        let top = self.base + self.height * self.axis;
+        Some(Aabb::new(
+            Point3::new(
+                self.base.x().min(top.x()) - extent.x(),
+                self.base.y().min(top.y()) - extent.y(),
+                self.base.z().min(top.z()) - extent.z(),
+            ),
+            Point3::new(
+                self.base.x().max(top.x()) + extent.x(),
+                self.base.y().max(top.y()) + extent.y(),
+                self.base.z().max(top.z()) + extent.z(),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    // a unit-radius cylinder standing on the origin, 2 units tall
+    fn cylinder(capped: bool) -> Cylinder {
+        Cylinder::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            1.0,
+            2.0,
+            capped,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_side_hit() {
+        let r = Ray::new(Point3::new(0.0, 1.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = cylinder(true).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 4.0);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(rec.v, 0.5);
+    }
+
+    #[test]
+    fn test_cap_hit() {
+        let r = Ray::new(Point3::new(0.5, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let rec = cylinder(true).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 3.0);
+        assert_eq!(rec.normal, Vec3::new(0.0, 1.0, 0.0));
+
+        // without caps, the ray goes down the tube and hits the inside of the far wall
+        let r = Ray::new(Point3::new(0.0, 3.0, 0.0), Vec3::new(0.5, -1.0, 0.0));
+        let rec = cylinder(false).hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!(!rec.front_face);
+        assert!((rec.p.x() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ray_above_top_misses() {
+        let r = Ray::new(Point3::new(0.0, 2.5, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(cylinder(true).hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(
+            cylinder(true).bounding_box(0.0, 1.0),
+            Some(Aabb::new(
+                Point3::new(-1.0, 0.0, -1.0),
+                Point3::new(1.0, 2.0, 1.0)
+            ))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// A flat disk of `radius` around `center`, facing along `normal`.
+pub struct Disk {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Disk {
+    pub fn new(center: Point3, normal: Vec3, radius: Float, mat_ptr: Arc<dyn Material>) -> Self {
+        Disk {
+            center,
+            normal: normal.unit_vector(),
+            radius,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let denom = dot(r.dir, self.normal);
+        // the ray is parallel to the disk
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = dot(self.center - r.orig, self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let p = r.at(t);
+        let offset = p - self.center;
+        let dist = offset.length();
+        if dist > self.radius {
+            return None;
+        }
+
+        // polar coordinates: u is the angle around the normal, v the distance from the center
+        let onb = Onb::build_from_w(self.normal);
+        let phi = Float::atan2(dot(offset, onb.v), dot(offset, onb.u)) + PI;
+
+        let hr = HitRecord {
+            p,
+            normal: self.normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: phi / (2.0 * PI),
+            v: dist / self.radius,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, self.normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // The disk extends radius * sin(angle between the axis and the normal) along each axis.
+        // Pad it so that axis-aligned disks don't get a zero-width box.
+        let extent = Vec3::new(1.0, 1.0, 1.0) - self.normal * self.normal;
+        let extent = extent.map(|e| self.radius * e.max(0.0).sqrt() + 0.0001);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    fn disk() -> Disk {
+        Disk::new(
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            0.5,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_centered_hit() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = disk().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 1.0);
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -1.0));
+        assert!(rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(rec.v, 0.0);
+    }
+
+    #[test]
+    fn test_hit_just_outside_radius_misses() {
+        let inside = Ray::new(Point3::new(0.49, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = disk().hit(&inside, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.v - 0.98).abs() < 1e-12);
+        assert!((0.0..=1.0).contains(&rec.u));
+
+        let outside = Ray::new(Point3::new(0.51, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(disk().hit(&outside, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_parallel_ray_misses() {
+        let r = Ray::new(Point3::new(-1.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(disk().hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = disk().bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(-0.5, -0.5, -1.0)).length() < 1e-3);
+        assert!((bbox.maximum - Point3::new(0.5, 0.5, -1.0)).length() < 1e-3);
+        assert!(bbox.maximum.z() > bbox.minimum.z());
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{Point3, Vec3};
+use crate::Float;
+use crate::{hit_sphere, HitRecord, Hittable, Ray};
+
+// A sphere scaled by `radii` along each axis.
+pub struct Ellipsoid {
+    pub center: Point3,
+    pub radii: Vec3,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Ellipsoid {
+    pub fn new(center: Point3, radii: Vec3, mat_ptr: Arc<dyn Material>) -> Self {
+        Ellipsoid {
+            center,
+            radii,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for Ellipsoid {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        // Intersect a unit sphere at the origin in scaled space; the ray parameter t is the same.
+        let scaled = Ray::with_time(
+            (r.orig - self.center) / self.radii,
+            r.dir / self.radii,
+            r.time,
+        );
+        let unit = Point3::new(0.0, 0.0, 0.0);
+        let rec = hit_sphere(unit, 1.0, &self.mat_ptr, &scaled, t_min, t_max)?;
+
+        let p = r.at(rec.t);
+        let outward_normal = ((p - self.center) / (self.radii * self.radii)).unit_vector();
+        let hr = HitRecord {
+            p,
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            ..rec
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        let radii = self.radii.map(Float::abs);
+        Some(Aabb::new(self.center - radii, self.center + radii))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::mat;
+    use crate::tolerance;
+    use crate::Sphere;
+
+    #[test]
+    fn test_equal_radii_match_sphere() {
+        let center = Point3::new(1.0, 0.0, -3.0);
+        let ellipsoid = Ellipsoid::new(center, Vec3::new(0.5, 0.5, 0.5), mat());
+        let sphere = Sphere::new(center, 0.5, mat());
+
+        for dir in [
+            Vec3::new(1.0, 0.0, -3.0),
+            Vec3::new(1.1, 0.2, -3.0),
+            Vec3::new(0.8, -0.1, -2.5),
+        ] {
+            let r = Ray::new(Point3::new(0.0, 0.0, 0.0), dir);
+            let a = ellipsoid.hit(&r, 0.001, Float::INFINITY).unwrap();
+            let b = sphere.hit(&r, 0.001, Float::INFINITY).unwrap();
+            assert!((a.t - b.t).abs() < tolerance(1e-9));
+            assert!((a.p - b.p).length() < tolerance(1e-9));
+            assert!((a.normal - b.normal).length() < tolerance(1e-9));
+            assert_eq!(a.front_face, b.front_face);
+        }
+    }
+
+    #[test]
+    fn test_elongated_ellipsoid() {
+        let center = Point3::new(0.0, 0.0, 0.0);
+        // tall and thin: 0.25 wide, 2 tall
+        let ellipsoid = Ellipsoid::new(center, Vec3::new(0.25, 2.0, 0.25), mat());
+        let sphere = Sphere::new(center, 2.0, mat());
+
+        // passes 1 unit to the side of the center
+        let grazing = Ray::new(Point3::new(1.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(sphere.hit(&grazing, 0.001, Float::INFINITY).is_some());
+        assert!(ellipsoid.hit(&grazing, 0.001, Float::INFINITY).is_none());
+
+        // the side of the ellipsoid faces +x
+        let side = Ray::new(Point3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = ellipsoid.hit(&side, 0.001, Float::INFINITY).unwrap();
+        assert!(rec.front_face);
+        assert!((rec.p.x() - 0.25 * Float::sqrt(0.75)).abs() < tolerance(1e-9));
+        assert!(rec.normal.x() > 0.9);
+        assert!((rec.normal.length() - 1.0).abs() < tolerance(1e-12));
+    }
+}
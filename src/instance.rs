@@ -0,0 +1,472 @@
+use crate::aabb::Aabb;
+use crate::mat3::Mat3;
+use crate::mat4::Mat4;
+use crate::util::degrees_to_radians;
+use crate::vec3::{Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray, INFINITY};
+
+// Instances: wrappers that move an object by transforming the rays that hit it.
+
+// Moves an object by `offset`.
+pub struct Translate {
+    pub offset: Vec3,
+    pub inner: Box<dyn Hittable>,
+}
+
+impl Translate {
+    pub fn new(inner: Box<dyn Hittable>, offset: Vec3) -> Self {
+        Translate { offset, inner }
+    }
+}
+
+impl Hittable for Translate {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let moved_r = Ray::with_time(r.orig - self.offset, r.dir, r.time);
+        let rec = self.inner.hit(&moved_r, t_min, t_max)?;
+        Some(HitRecord {
+            p: rec.p + self.offset,
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        let bbox = self.inner.bounding_box(time0, time1)?;
+        Some(Aabb::new(
+            bbox.minimum + self.offset,
+            bbox.maximum + self.offset,
+        ))
+    }
+}
+
+// Rotates an object about the Y axis.
+pub struct RotateY {
+    sin_theta: Float,
+    cos_theta: Float,
+    inner: Box<dyn Hittable>,
+    bbox: Option<Aabb>,
+}
+
+impl RotateY {
+    pub fn new(inner: Box<dyn Hittable>, angle_degrees: Float) -> Self {
+        let radians = degrees_to_radians(angle_degrees);
+        let sin_theta = radians.sin();
+        let cos_theta = radians.cos();
+
+        // Rotate all eight corners of the inner box and bound the result.
+        let bbox = inner.bounding_box(0.0, 1.0).map(|bbox| {
+            let mut min = Point3::new(INFINITY, INFINITY, INFINITY);
+            let mut max = Point3::new(-INFINITY, -INFINITY, -INFINITY);
+            for i in 0..2 {
+                for j in 0..2 {
+                    for k in 0..2 {
+                        let pick = |n: usize, a: usize| {
+                            if n == 1 {
+                                bbox.maximum[a]
+                            } else {
+                                bbox.minimum[a]
+                            }
+                        };
+                        let (x, y, z) = (pick(i, 0), pick(j, 1), pick(k, 2));
+                        let tester = Vec3::new(
+                            cos_theta * x + sin_theta * z,
+                            y,
+                            -sin_theta * x + cos_theta * z,
+                        );
+                        for c in 0..3 {
+                            min[c] = min[c].min(tester[c]);
+                            max[c] = max[c].max(tester[c]);
+                        }
+                    }
+                }
+            }
+            Aabb::new(min, max)
+        });
+
+        RotateY {
+            sin_theta,
+            cos_theta,
+            inner,
+            bbox,
+        }
+    }
+
+    // world space -> object space
+    fn to_object(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x() - self.sin_theta * v.z(),
+            v.y(),
+            self.sin_theta * v.x() + self.cos_theta * v.z(),
+        )
+    }
+
+    // object space -> world space
+    fn to_world(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            self.cos_theta * v.x() + self.sin_theta * v.z(),
+            v.y(),
+            -self.sin_theta * v.x() + self.cos_theta * v.z(),
+        )
+    }
+}
+
+impl Hittable for RotateY {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let rotated_r = Ray::with_time(self.to_object(r.orig), self.to_object(r.dir), r.time);
+        let rec = self.inner.hit(&rotated_r, t_min, t_max)?;
+        // Rotation preserves angles, so rec.front_face still holds.
+        Some(HitRecord {
+            p: self.to_world(rec.p),
+            normal: self.to_world(rec.normal),
+            tangent: self.to_world(rec.tangent),
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+// Rotates an object by an arbitrary rotation matrix.
+pub struct Rotate {
+    matrix: Mat3,
+    // rotations are orthonormal, so this is the transpose
+    inverse: Mat3,
+    inner: Box<dyn Hittable>,
+    bbox: Option<Aabb>,
+}
+
+impl Rotate {
+    pub fn new(inner: Box<dyn Hittable>, matrix: Mat3) -> Self {
+        let bbox = inner
+            .bounding_box(0.0, 1.0)
+            .map(|bbox| transform_box(&bbox, |corner| matrix * corner));
+
+        Rotate {
+            matrix,
+            inverse: matrix.transpose(),
+            inner,
+            bbox,
+        }
+    }
+
+    // Rotates about x, then y, then z.
+    pub fn from_euler(
+        inner: Box<dyn Hittable>,
+        x_degrees: Float,
+        y_degrees: Float,
+        z_degrees: Float,
+    ) -> Self {
+        Self::new(inner, Mat3::from_euler(x_degrees, y_degrees, z_degrees))
+    }
+
+    pub fn from_axis_angle(inner: Box<dyn Hittable>, axis: Vec3, angle_degrees: Float) -> Self {
+        Self::new(inner, Mat3::from_axis_angle(axis, angle_degrees))
+    }
+}
+
+impl Hittable for Rotate {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let rotated_r = Ray::with_time(self.inverse * r.orig, self.inverse * r.dir, r.time);
+        let rec = self.inner.hit(&rotated_r, t_min, t_max)?;
+        // Rotation preserves angles, so rec.front_face still holds.
+        Some(HitRecord {
+            p: self.matrix * rec.p,
+            normal: self.matrix * rec.normal,
+            tangent: self.matrix * rec.tangent,
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+// Rotates an object about the X axis.
+pub struct RotateX(Rotate);
+
+impl RotateX {
+    pub fn new(inner: Box<dyn Hittable>, angle_degrees: Float) -> Self {
+        RotateX(Rotate::new(inner, Mat3::rotation_x(angle_degrees)))
+    }
+}
+
+impl Hittable for RotateX {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        self.0.hit(r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        self.0.bounding_box(time0, time1)
+    }
+}
+
+// Rotates an object about the Z axis.
+pub struct RotateZ(Rotate);
+
+impl RotateZ {
+    pub fn new(inner: Box<dyn Hittable>, angle_degrees: Float) -> Self {
+        RotateZ(Rotate::new(inner, Mat3::rotation_z(angle_degrees)))
+    }
+}
+
+impl Hittable for RotateZ {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        self.0.hit(r, t_min, t_max)
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        self.0.bounding_box(time0, time1)
+    }
+}
+
+// Places an object with any affine transform: translation, rotation, shear, or
+// (non-uniform) scale.
+pub struct Transformed {
+    inner: Box<dyn Hittable>,
+    object_to_world: Mat4,
+    world_to_object: Mat4,
+    bbox: Option<Aabb>,
+}
+
+impl Transformed {
+    // Panics if `object_to_world` can't be inverted.
+    pub fn new(inner: Box<dyn Hittable>, object_to_world: Mat4) -> Self {
+        let world_to_object = object_to_world
+            .inverse()
+            .expect("an instance transform must be invertible");
+        let bbox = inner
+            .bounding_box(0.0, 1.0)
+            .map(|bbox| transform_box(&bbox, |corner| object_to_world.transform_point(corner)));
+        Transformed {
+            inner,
+            object_to_world,
+            world_to_object,
+            bbox,
+        }
+    }
+}
+
+impl Hittable for Transformed {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        // the direction isn't renormalized, so t means the same in both spaces
+        let object_r = Ray::with_time(
+            self.world_to_object.transform_point(r.orig),
+            self.world_to_object.transform_vector(r.dir),
+            r.time,
+        );
+        let rec = self.inner.hit(&object_r, t_min, t_max)?;
+        // Normals go by the inverse transpose, which keeps them perpendicular to the
+        // surface and on the same side of it, so rec.front_face still holds.
+        let normal = self
+            .world_to_object
+            .transpose()
+            .transform_vector(rec.normal)
+            .unit_vector();
+        let tangent = self.object_to_world.transform_vector(rec.tangent);
+        let tangent = if tangent.length_squared() > 0.0 {
+            tangent.unit_vector()
+        } else {
+            tangent
+        };
+        Some(HitRecord {
+            p: self.object_to_world.transform_point(rec.p),
+            normal,
+            tangent,
+            ..rec
+        })
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        self.bbox
+    }
+}
+
+// Transforms all eight corners of `bbox` and bounds the result.
+fn transform_box(bbox: &Aabb, transform: impl Fn(Point3) -> Point3) -> Aabb {
+    let mut min = Point3::new(INFINITY, INFINITY, INFINITY);
+    let mut max = Point3::new(-INFINITY, -INFINITY, -INFINITY);
+    for i in 0..8 {
+        let pick = |bit: usize, axis: usize| {
+            if i & bit == 0 {
+                bbox.minimum[axis]
+            } else {
+                bbox.maximum[axis]
+            }
+        };
+        let corner = transform(Point3::new(pick(1, 0), pick(2, 1), pick(4, 2)));
+        min = min.min(corner);
+        max = max.max(corner);
+    }
+    Aabb::new(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ellipsoid::Ellipsoid;
+    use crate::material::Lambertian;
+    use crate::rect::BoxPrim;
+    use crate::test_util::assert_vec_near;
+    use crate::tolerance;
+    use crate::vec3::COLOR_WHITE;
+    use crate::Sphere;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_translate_moves_hits() {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let moved = Translate::new(Box::new(sphere), Vec3::new(5.0, 0.0, 0.0));
+
+        let r = Ray::new(Point3::new(5.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let rec = moved.hit(&r, 0.001, INFINITY).unwrap();
+        assert_eq!(rec.t, 4.0);
+        assert_eq!(rec.p, Point3::new(5.0, 0.0, -1.0));
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, -1.0));
+
+        let through_origin = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(moved.hit(&through_origin, 0.001, INFINITY).is_none());
+        assert_eq!(
+            moved.bounding_box(0.0, 1.0),
+            Some(Aabb::new(
+                Point3::new(4.0, -1.0, -1.0),
+                Point3::new(6.0, 1.0, 1.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rotate_y_swaps_box_faces() {
+        // 1 wide (x), 2 deep (z)
+        let slab = BoxPrim::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 2.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let rotated = RotateY::new(Box::new(slab), 90.0);
+
+        // after rotating, the box spans x in [0, 2] and z in [-1, 0] (plus the sides' padding)
+        let bbox = rotated.bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(0.0, 0.0, -1.0)).length() < 1e-3);
+        assert!((bbox.maximum - Point3::new(2.0, 1.0, 0.0)).length() < 1e-3);
+
+        // a ray along -X now hits the face that was at z = 2
+        let r = Ray::new(Point3::new(5.0, 0.5, -0.5), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = rotated.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.t - 3.0).abs() < tolerance(1e-9));
+        assert!(rec.front_face);
+        assert_vec_near(rec.normal, Vec3::new(1.0, 0.0, 0.0));
+    }
+
+    fn slab() -> BoxPrim {
+        // 1 wide (x), 2 tall (y), 3 deep (z)
+        BoxPrim::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 2.0, 3.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_rotate_x_recomputes_bounding_box() {
+        // y runs into z, and z back into -y
+        let rotated = RotateX::new(Box::new(slab()), 90.0);
+        let bbox = rotated.bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(0.0, -3.0, 0.0)).length() < 1e-3);
+        assert!((bbox.maximum - Point3::new(1.0, 0.0, 2.0)).length() < 1e-3);
+
+        let r = Ray::new(Point3::new(0.5, -1.5, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = rotated.hit(&r, 0.001, INFINITY).unwrap();
+        assert!((rec.t - 3.0).abs() < tolerance(1e-9));
+        assert_vec_near(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_composed_rotations_match_general_rotation() {
+        let composed = RotateZ::new(Box::new(RotateX::new(Box::new(slab()), 90.0)), 90.0);
+        let general = Rotate::from_euler(Box::new(slab()), 90.0, 0.0, 90.0);
+        let composed_box = composed.bounding_box(0.0, 1.0).unwrap();
+        let general_box = general.bounding_box(0.0, 1.0).unwrap();
+        assert_vec_near(composed_box.minimum, general_box.minimum);
+        assert_vec_near(composed_box.maximum, general_box.maximum);
+        // x ends up along y, y along z and z along x: [0, 3] x [0, 1] x [0, 2]
+        assert!((general_box.minimum - Point3::new(0.0, 0.0, 0.0)).length() < 1e-3);
+        assert!((general_box.maximum - Point3::new(3.0, 1.0, 2.0)).length() < 1e-3);
+
+        crate::util::seed_rng(3);
+        for _ in 0..100 {
+            let r = Ray::new(
+                Point3::new(1.5, 0.5, 1.0) + 5.0 * Vec3::new_random_unit_vector(),
+                Vec3::new_random_unit_vector(),
+            );
+            let a = composed.hit(&r, 0.001, INFINITY);
+            let b = general.hit(&r, 0.001, INFINITY);
+            assert_eq!(a.is_some(), b.is_some());
+            if let (Some(a), Some(b)) = (a, b) {
+                assert!((a.t - b.t).abs() < tolerance(1e-9));
+                assert_vec_near(a.normal, b.normal);
+            }
+        }
+    }
+
+    // Both objects report the same hits for random rays aimed around `center`.
+    fn assert_same_hits(a: &dyn Hittable, b: &dyn Hittable, center: Point3) {
+        let (box_a, box_b) = (
+            a.bounding_box(0.0, 1.0).unwrap(),
+            b.bounding_box(0.0, 1.0).unwrap(),
+        );
+        assert_vec_near(box_a.minimum, box_b.minimum);
+        assert_vec_near(box_a.maximum, box_b.maximum);
+
+        crate::util::seed_rng(4);
+        let mut hits = 0;
+        for _ in 0..200 {
+            let origin = center + 6.0 * Vec3::new_random_unit_vector();
+            let target = center + 1.5 * Vec3::new_random_in_unit_sphere();
+            let r = Ray::new(origin, target - origin);
+            let (hit_a, hit_b) = (a.hit(&r, 0.001, INFINITY), b.hit(&r, 0.001, INFINITY));
+            assert_eq!(hit_a.is_some(), hit_b.is_some());
+            if let (Some(hit_a), Some(hit_b)) = (hit_a, hit_b) {
+                hits += 1;
+                assert!((hit_a.t - hit_b.t).abs() < tolerance(1e-9));
+                assert_vec_near(hit_a.p, hit_b.p);
+                assert_vec_near(hit_a.normal, hit_b.normal);
+                assert_eq!(hit_a.front_face, hit_b.front_face);
+            }
+        }
+        assert!(hits > 50, "only {hits} rays hit");
+    }
+
+    #[test]
+    fn test_transformed_matches_translate_and_rotate() {
+        let offset = Vec3::new(2.0, -1.0, 0.5);
+        assert_same_hits(
+            &Transformed::new(Box::new(slab()), Mat4::translation(offset)),
+            &Translate::new(Box::new(slab()), offset),
+            Point3::new(0.5, 1.5, 1.0) + offset,
+        );
+        assert_same_hits(
+            &Transformed::new(Box::new(slab()), Mat4::from_mat3(Mat3::rotation_y(30.0))),
+            &RotateY::new(Box::new(slab()), 30.0),
+            Point3::new(0.5, 1.5, 1.0),
+        );
+    }
+
+    #[test]
+    fn test_transformed_scale_matches_ellipsoid() {
+        let mat = Arc::new(Lambertian::new(COLOR_WHITE));
+        let radii = Vec3::new(3.0, 1.0, 0.5);
+        let unit_sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, mat.clone());
+        assert_same_hits(
+            &Transformed::new(Box::new(unit_sphere), Mat4::scaling(radii)),
+            &Ellipsoid::new(Point3::new(0.0, 0.0, 0.0), radii, mat),
+            Point3::new(0.0, 0.0, 0.0),
+        );
+    }
+}
@@ -1,57 +1,298 @@
-use std::{cmp::Ordering, sync::Arc};
+use std::sync::Arc;
 
+pub mod aabb;
+pub mod background;
+pub mod bokeh;
+pub mod bvh;
+pub mod capsule;
+pub mod cone;
+pub mod constant_medium;
+pub mod csg;
+pub mod cylinder;
+pub mod disk;
+pub mod ellipsoid;
+pub mod instance;
+pub mod light;
+pub mod mat3;
+pub mod mat4;
 pub mod material;
+pub mod mesh;
+pub mod moving_sphere;
+pub mod onb;
+pub mod pdf;
+pub mod perlin;
+pub mod plane;
+pub mod quad;
+pub mod rect;
+pub mod render;
+#[cfg(feature = "serde")]
+pub mod scene_desc;
+#[cfg(test)]
+mod test_util;
+pub mod texture;
+pub mod tonemap;
+pub mod torus;
+pub mod triangle;
 pub mod util;
 pub mod vec3;
 
+use aabb::{surrounding_box, Aabb};
+use background::Background;
+use bokeh::Bokeh;
+use light::{DirectionalLight, LightStrategy, PdfHittable, PointLight, SpotLight};
+use mat3::Mat3;
 use material::Material;
-use util::degrees_to_radians;
+use pdf::{HittablePdf, MixturePdf, Pdf};
+use render::RenderStats;
+use util::random_bounded;
 use vec3::{dot, Color, Point3, Vec3, COLOR_BLACK, COLOR_WHITE};
 
+// The floating point type used for all geometry and color math: `f64`, or `f32`
+// with the `f32` feature.
+#[cfg(not(feature = "f32"))]
+pub type Float = f64;
+#[cfg(feature = "f32")]
+pub type Float = f32;
+
+// Mathematical constants (`PI` and friends) for `Float`.
+#[cfg(feature = "f32")]
+pub use std::f32::consts;
+#[cfg(not(feature = "f32"))]
+pub use std::f64::consts;
+
 // Constants
-const INF: f64 = f64::INFINITY;
+pub const INFINITY: Float = Float::INFINITY;
+pub const PI: Float = consts::PI;
+
+pub use util::{degrees_to_radians, radians_to_degrees};
+
+#[cfg(test)]
+pub(crate) use test_util::tolerance;
 
 #[derive(Debug)]
 pub struct Ray {
     orig: Point3,
     dir: Vec3,
+    time: Float,
 }
 
 impl Ray {
     pub fn new(orig: Point3, dir: Vec3) -> Ray {
-        Ray { orig, dir }
+        Ray::with_time(orig, dir, 0.0)
+    }
+
+    pub fn with_time(orig: Point3, dir: Vec3, time: Float) -> Ray {
+        Ray { orig, dir, time }
+    }
+
+    pub fn origin(&self) -> Point3 {
+        self.orig
+    }
+
+    pub fn direction(&self) -> Vec3 {
+        self.dir
+    }
+
+    pub fn time(&self) -> Float {
+        self.time
     }
 
-    pub fn at(&self, t: f64) -> Point3 {
+    pub fn at(&self, t: Float) -> Point3 {
         self.orig + self.dir * t
     }
 
-    pub fn color(self, world: &impl Hittable, depth: i32) -> Color {
+    pub fn color(self, world: &impl Hittable, background: &dyn Background, depth: i32) -> Color {
+        self.color_with_lights(world, &[], &[], background, depth)
+    }
+
+    // The same as `color`, which samples no lights, but as a loop instead of recursion, so
+    // deep paths can't overflow the stack. Only material scattering is followed: no
+    // light sampling, `RenderStats` or `indirect_background_scale`, so `Renderer`
+    // doesn't use it.
+    pub fn trace_iterative(
+        self,
+        world: &impl Hittable,
+        background: &dyn Background,
+        max_depth: i32,
+    ) -> Color {
+        let mut ray = self;
+        let mut color = COLOR_BLACK;
+        // how much of the light found further along the path reaches the camera
+        let mut throughput = COLOR_WHITE;
+        for _ in 0..max_depth {
+            let Some(rec) = world.hit(&ray, 0.001, INFINITY) else {
+                return color + throughput * background.color(&ray);
+            };
+            color += throughput * rec.mat_ptr.emitted(rec.u, rec.v, rec.p, rec.front_face);
+            let Some(out) = rec.mat_ptr.scatter(&ray, &rec) else {
+                return color;
+            };
+            throughput = throughput * out.attenuation;
+            ray = out.scattered;
+        }
+        color
+    }
+
+    // Like `color`, but also samples each of `lights` directly at every diffuse hit, and
+    // samples `area_lights` too, combined with the material's own sampling by multiple
+    // importance sampling. The area lights must also be part of `world`.
+    pub fn color_with_lights(
+        self,
+        world: &impl Hittable,
+        lights: &[DirectionalLight],
+        area_lights: &[Arc<dyn PdfHittable>],
+        background: &dyn Background,
+        depth: i32,
+    ) -> Color {
+        let ctx = PathContext {
+            world,
+            lights,
+            point_lights: &[],
+            spot_lights: &[],
+            area_lights,
+            background,
+            strategy: LightStrategy::default(),
+            primary_depth: depth,
+            indirect_background_scale: 1.0,
+        };
+        self.trace_path(&ctx, depth, None, &mut RenderStats::default())
+    }
+
+    // Traces a path, counting its rays into `stats`. `brdf_pdf` is the density with which
+    // the previous bounce's material chose this ray, when that bounce also sampled the
+    // area lights; any light this ray finds is weighted against that light sampling.
+    pub(crate) fn trace_path<H: Hittable>(
+        self,
+        ctx: &PathContext<H>,
+        depth: i32,
+        brdf_pdf: Option<Float>,
+        stats: &mut RenderStats,
+    ) -> Color {
         // If we've exceeded the ray bounce limit, no more light is gathered.
         if depth <= 0 {
-            // eprintln!("depth <= 0");
+            stats.depth_exhausted += 1;
             return COLOR_BLACK;
         }
 
-        match world.hit(&self, 0.001, INF) {
-            Some(rec) => {
-                // eprintln!("hit? yes");
-                let out = rec.mat_ptr.scatter(&self, &rec);
-                match out {
-                    Some(out) => {
-                        // eprintln!("attenuation: {:?}", out.attenuation);
-                        // eprintln!("scattered:{:?}", out.scattered);
-                        out.attenuation * out.scattered.color(world, depth - 1)
-                    }
-                    None => COLOR_BLACK,
-                }
+        stats.rays_traced += 1;
+        let Some(rec) = ctx.world.hit(&self, 0.001, INFINITY) else {
+            // If the ray hits nothing, return the background color.
+            let background = ctx.background.color(&self);
+            if depth < ctx.primary_depth {
+                return ctx.indirect_background_scale * background;
             }
-            None => {
-                // eprintln!("hit? no");
-                let unit_direction = self.dir.unit_vector();
-                let t = 0.5 * (unit_direction.y() + 1.0);
-                COLOR_WHITE * (1.0 - t) + Color::new(0.5, 0.7, 1.0) * t
+            return background;
+        };
+
+        let mut emitted = rec.mat_ptr.emitted(rec.u, rec.v, rec.p, rec.front_face);
+        if let Some(brdf_pdf) = brdf_pdf {
+            emitted *= ctx.emission_weight(&self, brdf_pdf);
+        }
+        for light in ctx.lights {
+            emitted += light.contribution(ctx.world, &self, &rec);
+        }
+        for light in ctx.point_lights {
+            emitted += light.contribution(ctx.world, &self, &rec);
+        }
+        for light in ctx.spot_lights {
+            emitted += light.contribution(ctx.world, &self, &rec);
+        }
+        if !ctx.area_lights.is_empty() && ctx.strategy != LightStrategy::Brdf {
+            emitted += ctx.sample_area_light(&self, &rec);
+        }
+        let Some(out) = rec.mat_ptr.scatter(&self, &rec) else {
+            return emitted;
+        };
+
+        // a specular bounce has no density to weight by, nor could light sampling find it
+        let material_pdf = if out.is_specular {
+            None
+        } else {
+            rec.mat_ptr.scattering_pdf(&self, &rec, &out.scattered)
+        };
+        match material_pdf {
+            Some(material_pdf) if !ctx.area_lights.is_empty() => {
+                if material_pdf <= 0.0 {
+                    return emitted;
+                }
+                let f = rec.mat_ptr.brdf_cos(&self, &rec, &out.scattered);
+                emitted
+                    + f / material_pdf
+                        * out
+                            .scattered
+                            .trace_path(ctx, depth - 1, Some(material_pdf), stats)
             }
+            _ => emitted + out.attenuation * out.scattered.trace_path(ctx, depth - 1, None, stats),
+        }
+    }
+}
+
+// Everything a path needs to know about the scene besides the objects it hits.
+pub(crate) struct PathContext<'a, H: Hittable> {
+    pub world: &'a H,
+    pub lights: &'a [DirectionalLight],
+    pub point_lights: &'a [PointLight],
+    pub spot_lights: &'a [SpotLight],
+    pub area_lights: &'a [Arc<dyn PdfHittable>],
+    pub background: &'a dyn Background,
+    pub strategy: LightStrategy,
+    // the depth camera rays start with; rays with less are bounces
+    pub primary_depth: i32,
+    // how much of the background bounced rays see
+    pub indirect_background_scale: Float,
+}
+
+impl<H: Hittable> PathContext<'_, H> {
+    // Every area light, equally likely, as seen from `origin`.
+    fn area_light_pdf(&self, origin: Point3) -> MixturePdf<'_> {
+        MixturePdf::uniform(
+            self.area_lights
+                .iter()
+                .map(|light| Box::new(HittablePdf::new(origin, light.as_ref())) as Box<dyn Pdf>)
+                .collect(),
+        )
+    }
+
+    // How much of the light found by a material-sampled ray `r` to count.
+    fn emission_weight(&self, r: &Ray, brdf_pdf: Float) -> Float {
+        let light_pdf = self.area_light_pdf(r.orig).value(r.dir);
+        match self.strategy {
+            LightStrategy::Brdf => 1.0,
+            // light sampling already covered whatever it could have reached
+            LightStrategy::Light if light_pdf > 0.0 => 0.0,
+            LightStrategy::Light => 1.0,
+            // the balance heuristic
+            LightStrategy::Mis => brdf_pdf / (brdf_pdf + light_pdf),
+        }
+    }
+
+    // Direct light from one ray sent toward the area lights, for materials with a
+    // `scattering_pdf`.
+    fn sample_area_light(&self, r_in: &Ray, rec: &HitRecord) -> Color {
+        let light_pdf = self.area_light_pdf(rec.p);
+        let to_light = Ray::with_time(rec.p, light_pdf.generate(), r_in.time);
+        let light_pdf = light_pdf.value(to_light.dir);
+        let Some(brdf_pdf) = rec.mat_ptr.scattering_pdf(r_in, rec, &to_light) else {
+            return COLOR_BLACK;
+        };
+        if light_pdf <= 0.0 {
+            return COLOR_BLACK;
+        }
+
+        let f = rec.mat_ptr.brdf_cos(r_in, rec, &to_light);
+        // whatever the ray hits first: an occluder emits nothing, and an emitter in front
+        // of the light counts too. That's still unbiased: both strategies estimate the
+        // light arriving along a direction, whatever gives it off, and `light_pdf` is the
+        // density of that direction as drawn here, which `emission_weight` also uses.
+        let emitted = match self.world.hit(&to_light, 0.001, INFINITY) {
+            Some(hit) => hit.mat_ptr.emitted(hit.u, hit.v, hit.p, hit.front_face),
+            None => return COLOR_BLACK,
+        };
+
+        match self.strategy {
+            LightStrategy::Brdf => COLOR_BLACK,
+            LightStrategy::Light => f * emitted / light_pdf,
+            LightStrategy::Mis => f * emitted / (light_pdf + brdf_pdf),
         }
     }
 }
@@ -59,85 +300,171 @@ impl Ray {
 pub struct HitRecord {
     p: Point3,
     normal: Vec3,
-    t: f64,
+    // unit surface direction of increasing u, for tangent-space normal maps; zero for
+    // primitives that don't provide one
+    tangent: Vec3,
+    t: Float,
+    // surface coordinates of the hit point, for texturing
+    u: Float,
+    v: Float,
     front_face: bool,
     mat_ptr: Arc<dyn Material>,
+    // which object of the enclosing `HitList` (or `BvhNode`) was hit
+    obj_id: usize,
 }
 
 impl HitRecord {
-    fn with_face_normal(self: Self, r: &Ray, outward_normal: Vec3) -> HitRecord {
-        let (normal, front_face) = if dot(r.dir, outward_normal) > 0.0 {
-            (-outward_normal, false)
+    // Tells apart the objects of the world, e.g. to find silhouette edges.
+    pub fn obj_id(&self) -> usize {
+        self.obj_id
+    }
+
+    pub fn p(&self) -> Point3 {
+        self.p
+    }
+
+    // Points against the incoming ray.
+    pub fn normal(&self) -> Vec3 {
+        self.normal
+    }
+
+    pub fn tangent(&self) -> Vec3 {
+        self.tangent
+    }
+
+    pub fn t(&self) -> Float {
+        self.t
+    }
+
+    pub fn u(&self) -> Float {
+        self.u
+    }
+
+    pub fn v(&self) -> Float {
+        self.v
+    }
+
+    // Whether the ray hit the outside of the surface.
+    pub fn front_face(&self) -> bool {
+        self.front_face
+    }
+
+    pub fn mat_ptr(&self) -> &Arc<dyn Material> {
+        &self.mat_ptr
+    }
+
+    fn with_face_normal(self, r: &Ray, outward_normal: Vec3) -> HitRecord {
+        // The stored normal always points against the incident ray, so flip it
+        // when the ray hits the surface from the inside.
+        let front_face = dot(r.dir, outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
         } else {
-            (outward_normal, true)
+            -outward_normal
         };
 
         HitRecord {
-            p: self.p,
             normal,
-            t: self.t,
             front_face,
-            mat_ptr: Arc::clone(&self.mat_ptr),
+            ..self
         }
     }
 }
 
 pub trait Hittable: Send + Sync {
-    fn hit(&self, r: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord>;
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord>;
+    // Returns None for objects without a finite bounding box (or with no children).
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb>;
 }
 
 pub struct Sphere {
     pub center: Point3,
-    pub radius: f64,
+    pub radius: Float,
     pub mat_ptr: Arc<dyn Material>,
 }
 
 impl Sphere {
-    pub fn new(center: Point3, radius: f64, mat_ptr: Arc<dyn Material>) -> Self {
+    pub fn new(center: Point3, radius: Float, mat_ptr: Arc<dyn Material>) -> Self {
         Sphere {
             center,
             radius,
             mat_ptr,
         }
     }
+
+    // p: a given point on the sphere of radius one, centered at the origin.
+    // u: returned value [0,1] of angle around the Y axis from X=-1.
+    // v: returned value [0,1] of angle from Y=-1 to Y=+1.
+    pub fn get_sphere_uv(p: Point3) -> (Float, Float) {
+        let theta = Float::acos(-p.y());
+        let phi = Float::atan2(-p.z(), p.x()) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
 }
 
 impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
-        let oc = ray.orig - self.center;
-        let a = dot(ray.dir, ray.dir);
-        let half_b = dot(oc, ray.dir);
-        let c = dot(oc, oc) - self.radius * self.radius;
-        let discriminant = half_b * half_b - a * c;
-        if discriminant < 0.0 {
-            return None;
-        }
+    fn hit(&self, ray: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        hit_sphere(self.center, self.radius, &self.mat_ptr, ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // negative radii are used for hollow glass spheres
+        let radius = self.radius.abs();
+        let r = Vec3::new(radius, radius, radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+}
+
+pub(crate) fn hit_sphere(
+    center: Point3,
+    radius: Float,
+    mat_ptr: &Arc<dyn Material>,
+    ray: &Ray,
+    t_min: Float,
+    t_max: Float,
+) -> Option<HitRecord> {
+    let oc = ray.orig - center;
+    let a = dot(ray.dir, ray.dir);
+    let half_b = dot(oc, ray.dir);
+    let c = dot(oc, oc) - radius * radius;
+    let discriminant = half_b * half_b - a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
 
-        let sqrtd = f64::sqrt(discriminant);
+    let sqrtd = Float::sqrt(discriminant);
 
-        // try first root.. does it fall in time range?
-        let mut root = (-half_b - sqrtd) / a;
+    // try first root.. does it fall in time range?
+    let mut root = (-half_b - sqrtd) / a;
+    if root < t_min || t_max < root {
+        // try 2nd root
+        root = (-half_b + sqrtd) / a;
         if root < t_min || t_max < root {
-            // try 2nd root
-            root = (-half_b + sqrtd) / a;
-            if root < t_min || t_max < root {
-                return None;
-            }
+            return None;
         }
+    }
 
-        let t = root;
-        let p = ray.at(t);
-        let hr = HitRecord {
-            t,
-            p,
-            normal: (p - self.center) / self.radius,
-            front_face: false,
-            mat_ptr: Arc::clone(&self.mat_ptr),
-        };
-        let outward_normal = (p - self.center) / self.radius;
+    let t = root;
+    let p = ray.at(t);
+    let outward_normal = (p - center) / radius;
+    let (u, v) = Sphere::get_sphere_uv(outward_normal);
+    // u runs around the y axis; the poles have no tangent
+    let tangent = Vec3::new(outward_normal.z(), 0.0, -outward_normal.x())
+        .try_unit_vector()
+        .unwrap_or(Vec3::new(0.0, 0.0, 0.0));
+    let hr = HitRecord {
+        t,
+        p,
+        normal: outward_normal,
+        tangent,
+        u,
+        v,
+        front_face: false,
+        mat_ptr: Arc::clone(mat_ptr),
+        obj_id: 0,
+    };
 
-        Some(HitRecord::with_face_normal(hr, ray, outward_normal))
-    }
+    Some(HitRecord::with_face_normal(hr, ray, outward_normal))
 }
 
 pub struct HitList {
@@ -150,37 +477,67 @@ impl HitList {
             objects: Vec::new(),
         }
     }
-    pub fn clear(mut self: Self) {
+    pub fn from_vec(objects: Vec<Box<dyn Hittable>>) -> HitList {
+        HitList { objects }
+    }
+    pub fn clear(&mut self) {
         self.objects.clear();
     }
-    pub fn add(self: &mut Self, obj: Box<dyn Hittable>) {
+    pub fn add(&mut self, obj: Box<dyn Hittable>) {
         self.objects.push(obj);
     }
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Box<dyn Hittable>> {
+        self.objects.iter()
+    }
+}
+
+impl Default for HitList {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Hittable for HitList {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
-        // let mut closest_so_far = None;
-        let hits = self.objects.iter().map(|obj| obj.hit(ray, t_min, t_max));
-        let closest = hits.into_iter().min_by(|x, y| match (x, y) {
-            (None, None) => Ordering::Greater,
-            (Some(_x), None) => Ordering::Less,
-            (None, Some(_y)) => Ordering::Greater,
-            (Some(x), Some(y)) => {
-                if f64::min(x.t, y.t) == x.t {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                }
+    fn hit(&self, ray: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        // Each hit narrows t_max, so later objects only report hits closer than it.
+        let mut closest_so_far = t_max;
+        let mut closest = None;
+        for (obj_id, obj) in self.objects.iter().enumerate() {
+            if let Some(rec) = obj.hit(ray, t_min, closest_so_far) {
+                closest_so_far = rec.t;
+                closest = Some(HitRecord { obj_id, ..rec });
             }
-        });
+        }
+        closest
+    }
 
-        closest.unwrap()
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        let mut boxes = self
+            .objects
+            .iter()
+            .map(|obj| obj.bounding_box(time0, time1));
+        let first = boxes.next()??;
+        boxes.try_fold(first, |acc, b| Some(surrounding_box(&acc, &b?)))
     }
 }
 
 // #[derive(Copy, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    // rays fan out from a single point (the lens)
+    Perspective,
+    // rays are parallel, starting across the viewport
+    Orthographic,
+}
+
 pub struct Camera {
+    projection: Projection,
     origin: Point3,
     lower_left_corner: Point3,
     horizontal: Vec3,
@@ -188,18 +545,25 @@ pub struct Camera {
     u: Vec3,
     v: Vec3,
     w: Vec3,
-    lens_radius: f64,
+    lens_radius: Float,
+    // where on the lens rays start
+    bokeh: Bokeh,
+    // ray times are sampled uniformly from [shutter_open, shutter_close)
+    shutter_open: Float,
+    shutter_close: Float,
+    // scales every sample's color, before tone mapping
+    exposure: Float,
 }
 
 impl Camera {
     pub fn new(
         lookfrom: Point3,
         lookat: Point3,
-        vup: Vec3, // view up
-        vfov: f64, // vertical field-of-view (degrees)
-        aspect_ratio: f64,
-        aperture: f64,
-        focus_dist: f64,
+        vup: Vec3,   // view up
+        vfov: Float, // vertical field-of-view (degrees)
+        aspect_ratio: Float,
+        aperture: Float,
+        focus_dist: Float,
     ) -> Camera {
         let theta = degrees_to_radians(vfov);
         let h = (theta / 2.0).tan();
@@ -207,7 +571,7 @@ impl Camera {
         let viewport_width = aspect_ratio * viewport_height;
 
         let w = (lookfrom - lookat).unit_vector();
-        let u = vup.cross(w).unit_vector();
+        let u = side_axis(vup, w);
         let v = w.cross(u);
 
         let origin = lookfrom;
@@ -218,6 +582,7 @@ impl Camera {
         let lens_radius = aperture / 2.0;
 
         Camera {
+            projection: Projection::Perspective,
             origin,
             lower_left_corner,
             horizontal,
@@ -226,20 +591,245 @@ impl Camera {
             v,
             w,
             lens_radius,
+            bokeh: Bokeh::Circle,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            exposure: 1.0,
+        }
+    }
+
+    // A parallel-projection camera looking along `look_dir`, with a viewport
+    // `view_height` units tall centered on `center`.
+    pub fn orthographic(
+        center: Point3,
+        look_dir: Vec3,
+        vup: Vec3,
+        view_height: Float,
+        aspect_ratio: Float,
+    ) -> Camera {
+        let view_width = aspect_ratio * view_height;
+
+        let w = -look_dir.unit_vector();
+        let u = side_axis(vup, w);
+        let v = w.cross(u);
+
+        let horizontal = view_width * u;
+        let vertical = view_height * v;
+        let lower_left_corner = center - (horizontal / 2.0) - (vertical / 2.0);
+
+        Camera {
+            projection: Projection::Orthographic,
+            origin: center,
+            lower_left_corner,
+            horizontal,
+            vertical,
+            u,
+            v,
+            w,
+            lens_radius: 0.0,
+            bokeh: Bokeh::Circle,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            exposure: 1.0,
+        }
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn exposure(&self) -> Float {
+        self.exposure
+    }
+
+    // Keeps the shutter open from `shutter_open` to `shutter_close`, for motion blur.
+    pub fn with_shutter(self, shutter_open: Float, shutter_close: Float) -> Camera {
+        Camera {
+            shutter_open,
+            shutter_close,
+            ..self
+        }
+    }
+
+    // Shapes the aperture, and so the out-of-focus blur, like `bokeh`.
+    pub fn with_bokeh(self, bokeh: Bokeh) -> Camera {
+        Camera { bokeh, ..self }
+    }
+
+    // Turns the view by `roll_degrees` about its forward axis, counterclockwise as seen
+    // from behind the camera.
+    pub fn with_roll(self, roll_degrees: Float) -> Camera {
+        let roll = Mat3::from_axis_angle(self.w, roll_degrees);
+        let center = self.lower_left_corner + self.horizontal / 2.0 + self.vertical / 2.0;
+        let (horizontal, vertical) = (roll * self.horizontal, roll * self.vertical);
+        Camera {
+            lower_left_corner: center - horizontal / 2.0 - vertical / 2.0,
+            horizontal,
+            vertical,
+            u: roll * self.u,
+            v: roll * self.v,
+            ..self
+        }
+    }
+
+    // Brightens (above 1) or darkens (below 1) the image by a constant factor.
+    pub fn with_exposure(self, exposure: Float) -> Camera {
+        Camera { exposure, ..self }
+    }
+
+    // A pinhole camera: positionable like `new`, but with no defocus blur.
+    pub fn look_at(
+        lookfrom: Point3,
+        lookat: Point3,
+        vup: Vec3,
+        vfov_degrees: Float,
+        aspect_ratio: Float,
+    ) -> Camera {
+        Camera::new(lookfrom, lookat, vup, vfov_degrees, aspect_ratio, 0.0, 1.0)
+    }
+
+    pub fn get_ray(&self, s: Float, t: Float) -> Ray {
+        let time = if self.shutter_close > self.shutter_open {
+            random_bounded(self.shutter_open, self.shutter_close)
+        } else {
+            self.shutter_open
+        };
+        let viewport_point = self.lower_left_corner + self.horizontal * s + self.vertical * t;
+
+        match self.projection {
+            Projection::Perspective => {
+                let rd = self.lens_radius * self.bokeh.sample();
+                let offset = self.u * rd.x() + self.v * rd.y();
+                Ray::with_time(
+                    self.origin + offset,
+                    viewport_point - self.origin - offset,
+                    time,
+                )
+            }
+            Projection::Orthographic => Ray::with_time(viewport_point, -self.w, time),
+        }
+    }
+}
+
+// The camera's unit "right" direction, for one looking along -w. If `vup` is parallel to
+// w it can't tell right from left, so any upward direction perpendicular to w stands in.
+fn side_axis(vup: Vec3, w: Vec3) -> Vec3 {
+    let side = vup.cross(w);
+    if side.length_squared() > 1e-12 * vup.length_squared() {
+        return side.unit_vector();
+    }
+    // the world axis least aligned with w
+    let axes = [
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+    ];
+    let fallback = axes
+        .into_iter()
+        .min_by(|a, b| dot(*a, w).abs().total_cmp(&dot(*b, w).abs()))
+        .unwrap();
+    fallback.cross(w).unit_vector()
+}
+
+// Builds a thin-lens `Camera` by naming its settings, instead of passing them in order.
+// Defaults to a pinhole at the origin looking down -z, with a square 90° view.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraBuilder {
+    lookfrom: Point3,
+    lookat: Point3,
+    vup: Vec3,
+    vfov: Float,
+    aspect_ratio: Float,
+    aperture: Float,
+    focus_dist: Float,
+    shutter_open: Float,
+    shutter_close: Float,
+    roll: Float,
+}
+
+impl Default for CameraBuilder {
+    fn default() -> Self {
+        CameraBuilder {
+            lookfrom: Point3::new(0.0, 0.0, 0.0),
+            lookat: Point3::new(0.0, 0.0, -1.0),
+            vup: Vec3::new(0.0, 1.0, 0.0),
+            vfov: 90.0,
+            aspect_ratio: 1.0,
+            aperture: 0.0,
+            focus_dist: 1.0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            roll: 0.0,
+        }
+    }
+}
+
+impl CameraBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lookfrom(self, lookfrom: Point3) -> Self {
+        CameraBuilder { lookfrom, ..self }
+    }
+
+    pub fn lookat(self, lookat: Point3) -> Self {
+        CameraBuilder { lookat, ..self }
+    }
+
+    pub fn vup(self, vup: Vec3) -> Self {
+        CameraBuilder { vup, ..self }
+    }
+
+    // vertical field of view, in degrees
+    pub fn vfov(self, vfov: Float) -> Self {
+        CameraBuilder { vfov, ..self }
+    }
+
+    pub fn aspect_ratio(self, aspect_ratio: Float) -> Self {
+        CameraBuilder {
+            aspect_ratio,
+            ..self
+        }
+    }
+
+    pub fn aperture(self, aperture: Float) -> Self {
+        CameraBuilder { aperture, ..self }
+    }
+
+    pub fn focus_dist(self, focus_dist: Float) -> Self {
+        CameraBuilder { focus_dist, ..self }
+    }
+
+    pub fn shutter(self, shutter_open: Float, shutter_close: Float) -> Self {
+        CameraBuilder {
+            shutter_open,
+            shutter_close,
+            ..self
         }
     }
 
-    pub fn get_ray(&self, s: f64, t: f64) -> Ray {
-        let rd = self.lens_radius * Vec3::new_random_in_unit_disk();
-        let offset = self.u * rd.x() + self.v * rd.y();
-        Ray::new(
-            self.origin + offset,
-            self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset,
+    // in degrees, counterclockwise about the view direction
+    pub fn roll(self, roll: Float) -> Self {
+        CameraBuilder { roll, ..self }
+    }
+
+    pub fn build(self) -> Camera {
+        Camera::new(
+            self.lookfrom,
+            self.lookat,
+            self.vup,
+            self.vfov,
+            self.aspect_ratio,
+            self.aperture,
+            self.focus_dist,
         )
+        .with_shutter(self.shutter_open, self.shutter_close)
+        .with_roll(self.roll)
     }
 }
 
-pub fn clamp(x: f64, min: f64, max: f64) -> f64 {
+pub fn clamp(x: Float, min: Float, max: Float) -> Float {
     if x > max {
         return max;
     } else if x < min {
@@ -247,3 +837,607 @@ pub fn clamp(x: f64, min: f64, max: f64) -> f64 {
     }
     x
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use background::{GradientSky, SolidBackground};
+    use material::{Dialectric, DiffuseLight, Lambertian, Metal};
+    use quad::Quad;
+    use test_util::assert_vec_near;
+
+    #[test]
+    fn test_hit_from_inside_sphere_flips_normal() {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+        let rec = sphere.hit(&ray, 0.001, INFINITY).unwrap();
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(-1.0, 0.0, 0.0));
+        // normal points back toward the ray's origin
+        assert!(dot(rec.normal, ray.orig - rec.p) > 0.0);
+    }
+
+    #[test]
+    fn test_hit_from_outside_sphere_keeps_normal() {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let ray = Ray::new(Point3::new(-3.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+        let rec = sphere.hit(&ray, 0.001, INFINITY).unwrap();
+        assert!(rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_angle_conversions() {
+        assert!((degrees_to_radians(180.0) - PI).abs() < Float::EPSILON);
+        assert!((radians_to_degrees(PI / 2.0) - 90.0).abs() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_float_width() {
+        let width = if cfg!(feature = "f32") { 4 } else { 8 };
+        assert_eq!(std::mem::size_of::<Float>(), width);
+        assert_eq!(std::mem::size_of::<Vec3>(), 3 * width);
+    }
+
+    #[test]
+    fn test_sphere_tangent_follows_u() {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let hit = |origin: Point3| {
+            let ray = Ray::new(origin, -origin);
+            sphere.hit(&ray, 0.001, INFINITY).unwrap()
+        };
+
+        let rec = hit(Point3::new(3.0, 1.0, 2.0));
+        assert!(dot(rec.tangent, rec.normal).abs() < tolerance(1e-9));
+        assert!((rec.tangent.length() - 1.0).abs() < tolerance(1e-9));
+        // stepping along the tangent increases u
+        let (u, _) = Sphere::get_sphere_uv(rec.p + 0.01 * rec.tangent);
+        assert!(u > rec.u);
+
+        // no tangent at the poles
+        assert_eq!(
+            hit(Point3::new(0.0, 3.0, 0.0)).tangent,
+            Vec3::new(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_look_at_down_negative_z() {
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            2.0,
+        );
+
+        // vfov 90 => viewport is 2 high and 4 wide, one unit in front of the camera
+        assert_vec_near(camera.get_ray(0.5, 0.5).dir, Vec3::new(0.0, 0.0, -1.0));
+        assert_vec_near(camera.get_ray(0.0, 0.0).dir, Vec3::new(-2.0, -1.0, -1.0));
+        assert_vec_near(camera.get_ray(1.0, 1.0).dir, Vec3::new(2.0, 1.0, -1.0));
+    }
+
+    #[test]
+    fn test_look_at_from_positive_x() {
+        let camera = Camera::look_at(
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            2.0,
+        );
+
+        let center = camera.get_ray(0.5, 0.5);
+        assert_vec_near(center.orig, Point3::new(1.0, 0.0, 0.0));
+        assert_vec_near(center.dir, Vec3::new(-1.0, 0.0, 0.0));
+        // looking down -X, "right" in the image is -Z
+        assert_vec_near(camera.get_ray(1.0, 0.5).dir, Vec3::new(-1.0, 0.0, -2.0));
+        assert_vec_near(camera.get_ray(0.5, 1.0).dir, Vec3::new(-1.0, 1.0, 0.0));
+
+        // the center ray hits a sphere sitting at the look-at point
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            0.5,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let rec = sphere.hit(&center, 0.001, INFINITY).unwrap();
+        assert_vec_near(rec.p, Point3::new(0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_camera_builder_defaults_match_new() {
+        let built = CameraBuilder::new().aspect_ratio(1.5).build();
+        let direct = Camera::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.5,
+            0.0,
+            1.0,
+        );
+        for (s, t) in [(0.0, 0.0), (0.25, 0.75), (1.0, 0.5)] {
+            let (a, b) = (built.get_ray(s, t), direct.get_ray(s, t));
+            assert_eq!((a.orig, a.dir, a.time), (b.orig, b.dir, b.time));
+        }
+
+        // a narrower view: the top edge of the image is closer to straight ahead
+        let narrow = CameraBuilder::new().aspect_ratio(1.5).vfov(30.0).build();
+        let top = |camera: &Camera| camera.get_ray(0.5, 1.0).dir.unit_vector().y();
+        assert!((top(&built) - Float::sqrt(0.5)).abs() < tolerance(1e-9));
+        assert!((top(&narrow) - degrees_to_radians(15.0).sin()).abs() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_roll_swaps_viewport_axes() {
+        let camera = CameraBuilder::new().aspect_ratio(2.0).build();
+        let rolled = CameraBuilder::new().aspect_ratio(2.0).roll(90.0).build();
+        // the image's right edge now points up, and its top edge left
+        assert_vec_near(rolled.horizontal, camera.horizontal.length() * camera.v);
+        assert_vec_near(rolled.vertical, -camera.vertical.length() * camera.u);
+        assert_vec_near(rolled.get_ray(0.5, 0.5).dir, camera.get_ray(0.5, 0.5).dir);
+        assert_vec_near(rolled.get_ray(1.0, 0.5).dir, Vec3::new(0.0, 2.0, -1.0));
+    }
+
+    #[test]
+    fn test_vup_parallel_to_view_still_makes_rays() {
+        for lookat in [Point3::new(0.0, -1.0, 0.0), Point3::new(0.0, 1.0, 0.0)] {
+            let camera = Camera::look_at(
+                Point3::new(0.0, 0.0, 0.0),
+                lookat,
+                Vec3::new(0.0, 1.0, 0.0),
+                90.0,
+                1.0,
+            );
+            for (s, t) in [(0.0, 0.0), (0.5, 0.5), (1.0, 0.3)] {
+                let r = camera.get_ray(s, t);
+                assert!(!r.dir.x().is_nan() && !r.dir.y().is_nan() && !r.dir.z().is_nan());
+            }
+            let center = camera.get_ray(0.5, 0.5).dir.unit_vector();
+            assert_vec_near(center, lookat);
+            assert!((camera.horizontal.length() - 2.0).abs() < tolerance(1e-9));
+        }
+    }
+
+    #[test]
+    fn test_zero_aperture_matches_pinhole() {
+        let lookfrom = Point3::new(3.0, 2.0, 1.0);
+        let lookat = Point3::new(0.0, 0.0, 0.0);
+        let vup = Vec3::new(0.0, 1.0, 0.0);
+        let pinhole = Camera::look_at(lookfrom, lookat, vup, 40.0, 1.5);
+        let thin_lens = Camera::new(lookfrom, lookat, vup, 40.0, 1.5, 0.0, 7.0);
+
+        for (s, t) in [(0.0, 0.0), (0.25, 0.75), (1.0, 0.5)] {
+            let a = pinhole.get_ray(s, t);
+            let b = thin_lens.get_ray(s, t);
+            assert_eq!(a.orig, b.orig);
+            assert_vec_near(a.dir.unit_vector(), b.dir.unit_vector());
+        }
+    }
+
+    #[test]
+    fn test_defocus_rays_converge_on_focus_plane() {
+        let camera = Camera::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+            1.0,
+            5.0,
+        );
+
+        let first = camera.get_ray(0.3, 0.6);
+        let mut origins_vary = false;
+        for _ in 0..20 {
+            let ray = camera.get_ray(0.3, 0.6);
+            origins_vary |= ray.orig != first.orig;
+            assert!(ray.orig.length() <= 0.5);
+            assert_vec_near(ray.at(1.0), first.at(1.0));
+        }
+        assert!(origins_vary);
+        assert!((first.at(1.0).z() + 5.0).abs() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_bokeh_shapes_out_of_focus_highlights() {
+        crate::util::seed_rng(5);
+        // the blur spot of a point at twice the focus distance: the lens shape, flipped
+        let spot_extent = |bokeh: Bokeh| {
+            let camera = Camera::new(
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(0.0, 0.0, -1.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                90.0,
+                1.0,
+                2.0,
+                1.0,
+            )
+            .with_bokeh(bokeh);
+            (0..20000).fold((0.0 as Float, 0.0 as Float), |(x, y), _| {
+                let ray = camera.get_ray(0.5, 0.5);
+                let p = ray.at(-2.0 / ray.dir.z());
+                (x.max(p.x().abs()), y.max(p.y().abs()))
+            })
+        };
+
+        // corners up and down reach further than the flat sides at left and right
+        let (width, height) = spot_extent(Bokeh::Polygon(6));
+        assert!(height <= 1.0 + tolerance(1e-9));
+        assert!(height / width > 1.1, "hexagonal spot {width} by {height}");
+        let (width, height) = spot_extent(Bokeh::Circle);
+        assert!(
+            (height / width - 1.0).abs() < 0.03,
+            "round spot {width} by {height}"
+        );
+    }
+
+    #[test]
+    fn test_hit_list_bounding_box() {
+        let mut world = HitList::new();
+        assert!(world.bounding_box(0.0, 1.0).is_none());
+
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::clone(&mat),
+        )));
+        world.add(Box::new(Sphere::new(Point3::new(3.0, 0.0, 0.0), 0.5, mat)));
+        assert_eq!(
+            world.bounding_box(0.0, 1.0),
+            Some(Aabb::new(
+                Point3::new(-1.0, -1.0, -1.0),
+                Point3::new(3.5, 1.0, 1.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_get_sphere_uv() {
+        let uv = |x, y, z| Sphere::get_sphere_uv(Point3::new(x, y, z));
+        assert_eq!(uv(1.0, 0.0, 0.0), (0.5, 0.5));
+        assert_eq!(uv(-1.0, 0.0, 0.0), (0.0, 0.5));
+        assert_eq!(uv(0.0, 0.0, 1.0), (0.25, 0.5));
+        assert_eq!(uv(0.0, 0.0, -1.0), (0.75, 0.5));
+        assert_eq!(uv(0.0, 1.0, 0.0).1, 1.0);
+        assert_eq!(uv(0.0, -1.0, 0.0).1, 0.0);
+    }
+
+    #[test]
+    fn test_sphere_hit_sets_uv() {
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, -5.0),
+            2.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let ray = Ray::new(Point3::new(5.0, 0.0, -5.0), Vec3::new(-1.0, 0.0, 0.0));
+
+        let rec = sphere.hit(&ray, 0.001, INFINITY).unwrap();
+        assert_eq!((rec.u, rec.v), (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_emissive_sphere_on_black_background() {
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -3.0),
+            1.0,
+            Arc::new(DiffuseLight::new(Color::new(4.0, 4.0, 4.0))),
+        )));
+
+        let toward_light = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert_eq!(
+            toward_light.color(&world, &SolidBackground(COLOR_BLACK), 10),
+            Color::new(4.0, 4.0, 4.0)
+        );
+        let away = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            away.color(&world, &SolidBackground(COLOR_BLACK), 10),
+            COLOR_BLACK
+        );
+    }
+
+    #[test]
+    fn test_camera_shutter_samples_ray_times() {
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+        );
+        for _ in 0..50 {
+            assert_eq!(camera.get_ray(0.5, 0.5).time, 0.0);
+        }
+
+        let camera = camera.with_shutter(1.0, 2.0);
+        let times: Vec<Float> = (0..500).map(|_| camera.get_ray(0.5, 0.5).time).collect();
+        assert!(times.iter().all(|t| (1.0..2.0).contains(t)));
+        // spread across the whole interval
+        assert!(times.iter().any(|&t| t < 1.05));
+        assert!(times.iter().any(|&t| t > 1.95));
+    }
+
+    // A material written only against the public API: it shades by normal, and only on
+    // the outside.
+    struct OutsideNormals;
+
+    impl Material for OutsideNormals {
+        fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<material::ScatterResult> {
+            if !rec.front_face() {
+                return None;
+            }
+            Some(material::ScatterResult {
+                scattered: Ray::with_time(rec.p(), rec.normal(), r.time()),
+                attenuation: 0.5 * (rec.normal() + COLOR_WHITE),
+                is_specular: false,
+            })
+        }
+    }
+
+    #[test]
+    fn test_hit_record_accessors_support_external_materials() {
+        let sphere = Sphere::new(Point3::new(0.0, 0.0, 0.0), 1.0, Arc::new(OutsideNormals));
+        let outside = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = sphere.hit(&outside, 0.001, INFINITY).unwrap();
+        assert_eq!((rec.t(), rec.p()), (4.0, Point3::new(0.0, 0.0, 1.0)));
+        let scattered = rec.mat_ptr().scatter(&outside, &rec).unwrap();
+        assert_eq!(scattered.attenuation, Color::new(0.5, 0.5, 1.0));
+        assert_eq!(scattered.scattered.dir, Vec3::new(0.0, 0.0, 1.0));
+
+        let inside = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = sphere.hit(&inside, 0.001, INFINITY).unwrap();
+        assert!(!rec.front_face());
+        assert!(rec.mat_ptr().scatter(&inside, &rec).is_none());
+    }
+
+    #[test]
+    fn test_hit_list_accessors() {
+        let empty = HitList::new();
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let objects: Vec<Box<dyn Hittable>> = (0..3)
+            .map(|i| {
+                let center = Point3::new(2.0 * i as Float, 0.0, 0.0);
+                Box::new(Sphere::new(center, 0.5, Arc::clone(&mat))) as Box<dyn Hittable>
+            })
+            .collect();
+        let world = HitList::from_vec(objects);
+        assert_eq!(world.len(), 3);
+        assert!(!world.is_empty());
+        let centers: Vec<Float> = world
+            .iter()
+            .map(|obj| obj.bounding_box(0.0, 1.0).unwrap().centroid().x())
+            .collect();
+        assert_eq!(centers, vec![0.0, 2.0, 4.0]);
+    }
+
+    #[test]
+    fn test_iterative_trace_matches_recursive_color() {
+        let mut outdoors = HitList::new();
+        outdoors.add(Box::new(Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.0))),
+        )));
+        outdoors.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.3)),
+        )));
+        let mut lamp_room = HitList::new();
+        lamp_room.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Arc::new(Dialectric::new(1.5)),
+        )));
+        lamp_room.add(Box::new(Sphere::new(
+            Point3::new(0.0, 3.0, -1.0),
+            1.0,
+            Arc::new(DiffuseLight::new(Color::new(4.0, 4.0, 4.0))),
+        )));
+        lamp_room.add(Box::new(Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+
+        let scenes: [(&HitList, &dyn Background); 2] = [
+            (&outdoors, &GradientSky::default()),
+            (&lamp_room, &SolidBackground(COLOR_BLACK)),
+        ];
+        for (world, background) in scenes {
+            for seed in 0..50 {
+                let s = seed as Float;
+                let r = || {
+                    let dir = Vec3::new(0.02 * s - 0.5, 0.3 - 0.012 * s, -2.0);
+                    Ray::new(Point3::new(0.0, 0.0, 1.0), dir)
+                };
+                crate::util::seed_rng(seed);
+                let recursive = r().color(world, background, 20);
+                crate::util::seed_rng(seed);
+                let iterative = r().trace_iterative(world, background, 20);
+                assert!(
+                    (recursive - iterative).length() < tolerance(1e-9),
+                    "{recursive:?} recursively, {iterative:?} iteratively"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_trace_handles_deep_paths() {
+        // two slightly dark mirrors facing each other across z = 0, 2 wide in x; a ray
+        // drifting 1 / (2 * bounces) in x per unit of z escapes past their edge to the
+        // sky after exactly `bounces` reflections
+        let bounces = 1000;
+        let albedo = 0.998;
+        let mirror: Arc<dyn Material> = Arc::new(Metal::new(albedo * COLOR_WHITE, 0.0));
+        let mut world = HitList::new();
+        for z in [-1.0, 1.0] {
+            world.add(Box::new(Quad::new(
+                Point3::new(-1.0, -10.0, z),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 20.0, 0.0),
+                Arc::clone(&mirror),
+            )));
+        }
+        let r = || {
+            let drift = 1.0 / (2.0 * bounces as Float);
+            Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(drift, 0.0, 1.0))
+        };
+        let sky = SolidBackground(Color::new(10.0, 10.0, 10.0));
+
+        let escaped = r().trace_iterative(&world, &sky, bounces + 1);
+        let expected = 10.0 * albedo.powi(bounces);
+        assert!(
+            (escaped.x() - expected).abs() < tolerance(1e-9) * expected,
+            "{escaped:?}, expected {expected}"
+        );
+        // one ray short, the path runs out of depth still between the mirrors
+        assert_eq!(r().trace_iterative(&world, &sky, bounces), COLOR_BLACK);
+        assert_eq!(r().trace_iterative(&world, &sky, 0), COLOR_BLACK);
+    }
+
+    #[test]
+    fn test_hit_list_clear_keeps_list_usable() {
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let sphere = |x| Box::new(Sphere::new(Point3::new(x, 0.0, 0.0), 0.5, Arc::clone(&mat)));
+        let mut world = HitList::new();
+        world.add(sphere(0.0));
+        world.add(sphere(2.0));
+        assert_eq!(world.len(), 2);
+
+        world.clear();
+        assert!(world.is_empty());
+        let r = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(world.hit(&r, 0.001, INFINITY).is_none());
+
+        world.add(sphere(0.0));
+        assert_eq!(world.len(), 1);
+        assert!(world.hit(&r, 0.001, INFINITY).is_some());
+    }
+
+    #[test]
+    fn test_hit_list_reports_object_ids() {
+        let mut list = HitList::new();
+        for x in [-2.0, 2.0] {
+            list.add(Box::new(Sphere::new(
+                Point3::new(x, 0.0, 0.0),
+                1.0,
+                Arc::new(Lambertian::new(COLOR_WHITE)),
+            )));
+        }
+        let hit_from = |x: Float| {
+            let ray = Ray::new(Point3::new(x, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+            list.hit(&ray, 0.001, INFINITY).unwrap().obj_id()
+        };
+        assert_eq!((hit_from(-2.0), hit_from(2.0)), (0, 1));
+    }
+
+    #[test]
+    fn test_empty_hit_list_misses() {
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(HitList::new().hit(&ray, 0.0, INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_hit_list_returns_nearest_hit() {
+        let mut world = HitList::new();
+        for z in [-6.0, -3.0, -9.0] {
+            world.add(Box::new(Sphere::new(
+                Point3::new(0.0, 0.0, z),
+                2.0,
+                Arc::new(Lambertian::new(COLOR_WHITE)),
+            )));
+        }
+
+        let ray = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = world.hit(&ray, 0.001, INFINITY).unwrap();
+        assert_eq!(rec.t, 1.0);
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn test_scene_types_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+        assert_send_sync::<Arc<dyn Material>>();
+        assert_send_sync::<Arc<dyn texture::Texture>>();
+        assert_send_sync::<Box<dyn Hittable>>();
+        assert_send_sync::<HitRecord>();
+        assert_send_sync::<HitList>();
+        assert_send_sync::<Camera>();
+    }
+
+    #[test]
+    fn test_ray_accessors() {
+        let ray = Ray::with_time(Point3::new(1.0, 2.0, 3.0), Vec3::new(0.0, -1.0, 0.0), 0.5);
+        assert_eq!(ray.origin(), Point3::new(1.0, 2.0, 3.0));
+        assert_eq!(ray.direction(), Vec3::new(0.0, -1.0, 0.0));
+        assert_eq!(ray.time(), 0.5);
+    }
+
+    #[test]
+    fn test_orthographic_rays_are_parallel() {
+        let camera = Camera::orthographic(
+            Point3::new(0.0, 0.0, 5.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            2.0,
+            2.0,
+        );
+        assert_eq!(camera.projection(), Projection::Orthographic);
+
+        let a = camera.get_ray(0.0, 0.0);
+        let b = camera.get_ray(1.0, 0.75);
+        assert_eq!(a.dir, b.dir);
+        assert_vec_near(a.dir, Vec3::new(0.0, 0.0, -1.0));
+        assert_vec_near(a.orig, Point3::new(-2.0, -1.0, 5.0));
+        assert_vec_near(b.orig, Point3::new(2.0, 0.5, 5.0));
+    }
+
+    #[test]
+    fn test_orthographic_size_is_independent_of_distance() {
+        let camera = Camera::orthographic(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            4.0,
+            1.0,
+        );
+        let mat: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let near = Sphere::new(Point3::new(0.0, 0.0, -2.0), 1.0, Arc::clone(&mat));
+        let far = Sphere::new(Point3::new(0.0, 0.0, -50.0), 1.0, mat);
+
+        // sweep a scanline across the middle of the image and count pixels covered
+        let coverage = |sphere: &Sphere| {
+            (0..100)
+                .filter(|i| {
+                    let ray = camera.get_ray(*i as Float / 99.0, 0.5);
+                    sphere.hit(&ray, 0.001, INFINITY).is_some()
+                })
+                .count()
+        };
+        assert_eq!(coverage(&near), coverage(&far));
+        assert!(coverage(&near) > 40 && coverage(&near) < 60);
+    }
+}
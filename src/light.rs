@@ -0,0 +1,315 @@
+use crate::consts::PI;
+
+use crate::onb::Onb;
+use crate::util::{degrees_to_radians, random_double};
+use crate::vec3::{dot, Color, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray, Sphere, INFINITY};
+
+// Geometry that can be sampled directly, so that the integrator can send rays toward it.
+pub trait PdfHittable: Hittable {
+    // Probability density (over solid angle) of `random_to(origin)` choosing `direction`.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> Float;
+
+    // A random direction from `origin` toward this object.
+    fn random_to(&self, origin: Point3) -> Vec3;
+}
+
+impl PdfHittable for Sphere {
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> Float {
+        if self
+            .hit(&Ray::new(origin, direction), 0.001, INFINITY)
+            .is_none()
+        {
+            return 0.0;
+        }
+        let cos_theta_max = self.cos_theta_max(origin);
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        1.0 / solid_angle
+    }
+
+    fn random_to(&self, origin: Point3) -> Vec3 {
+        // uniform over the cone of directions that the sphere covers
+        let cos_theta_max = self.cos_theta_max(origin);
+        let r1 = random_double();
+        let r2 = random_double();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = Float::sqrt(1.0 - z * z);
+        Onb::build_from_w(self.center - origin).local(
+            phi.cos() * sin_theta,
+            phi.sin() * sin_theta,
+            z,
+        )
+    }
+}
+
+impl Sphere {
+    // cosine of the half-angle of the cone the sphere covers, seen from `origin`
+    fn cos_theta_max(&self, origin: Point3) -> Float {
+        let distance_squared = dot(self.center - origin, self.center - origin);
+        Float::sqrt(Float::max(
+            1.0 - self.radius * self.radius / distance_squared,
+            0.0,
+        ))
+    }
+}
+
+// How diffuse and glossy bounces gather light from the area lights.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LightStrategy {
+    // only follow the material's own scattering, as if there were no area lights
+    Brdf,
+    // only count light reached by rays sent toward the area lights
+    Light,
+    // both, combined with the balance heuristic
+    #[default]
+    Mis,
+}
+
+// A light infinitely far away, like the sun: all of its rays are parallel.
+#[derive(Debug, Clone, Copy)]
+pub struct DirectionalLight {
+    // the direction the light travels in, i.e. pointing away from the sun
+    pub direction: Vec3,
+    // irradiance on a surface facing the light
+    pub color: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, color: Color) -> Self {
+        DirectionalLight {
+            direction: direction.unit_vector(),
+            color,
+        }
+    }
+
+    // Direct light reflected toward `r_in` from this light, or black if the hit point is in shadow.
+    pub fn contribution(&self, world: &impl Hittable, r_in: &Ray, rec: &HitRecord) -> Color {
+        let to_light = Ray::with_time(rec.p, -self.direction, r_in.time);
+        if dot(rec.normal, to_light.dir) <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let f = rec.mat_ptr.brdf_cos(r_in, rec, &to_light);
+        if f == Color::new(0.0, 0.0, 0.0) || world.hit(&to_light, 0.001, INFINITY).is_some() {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        self.color * f
+    }
+}
+
+// A light at a single point, shining equally in every direction and falling off with the
+// square of the distance.
+#[derive(Debug, Clone, Copy)]
+pub struct PointLight {
+    pub position: Point3,
+    // irradiance on a surface facing the light from one unit away
+    pub intensity: Color,
+}
+
+impl PointLight {
+    pub fn new(position: Point3, intensity: Color) -> Self {
+        PointLight {
+            position,
+            intensity,
+        }
+    }
+
+    // Direct light reflected toward `r_in` from this light, or black if something lies
+    // between the hit point and the light.
+    pub fn contribution(&self, world: &impl Hittable, r_in: &Ray, rec: &HitRecord) -> Color {
+        // reaches the light at t = 1
+        let to_light = Ray::with_time(rec.p, self.position - rec.p, r_in.time);
+        if dot(rec.normal, to_light.dir) <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let f = rec.mat_ptr.brdf_cos(r_in, rec, &to_light);
+        if f == Color::new(0.0, 0.0, 0.0) || world.hit(&to_light, 0.001, 1.0).is_some() {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        self.intensity * f / to_light.dir.length_squared()
+    }
+}
+
+// A point light that only shines in a cone: at full strength within `inner_angle` of
+// `direction`, fading smoothly to nothing at `outer_angle`, and dark beyond.
+#[derive(Debug, Clone, Copy)]
+pub struct SpotLight {
+    pub position: Point3,
+    // the cone's axis, pointing away from the light
+    pub direction: Vec3,
+    // irradiance on a surface facing the light from one unit away, on the axis
+    pub intensity: Color,
+    // half-angles of the cone, in degrees
+    pub inner_angle: Float,
+    pub outer_angle: Float,
+}
+
+impl SpotLight {
+    pub fn new(
+        position: Point3,
+        direction: Vec3,
+        intensity: Color,
+        inner_angle: Float,
+        outer_angle: Float,
+    ) -> Self {
+        SpotLight {
+            position,
+            direction: direction.unit_vector(),
+            intensity,
+            inner_angle,
+            outer_angle,
+        }
+    }
+
+    // How much of the light leaves toward `point`, between 0 and 1.
+    pub fn falloff(&self, point: Point3) -> Float {
+        let cos_angle = dot((point - self.position).unit_vector(), self.direction);
+        let cos_inner = degrees_to_radians(self.inner_angle).cos();
+        let cos_outer = degrees_to_radians(self.outer_angle).cos();
+        if cos_angle >= cos_inner {
+            return 1.0;
+        }
+        if cos_angle <= cos_outer {
+            return 0.0;
+        }
+        // smoothstep across the edge of the cone
+        let x = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+        x * x * (3.0 - 2.0 * x)
+    }
+
+    // Direct light reflected toward `r_in` from this light, or black if the hit point is
+    // outside the cone or something lies between it and the light.
+    pub fn contribution(&self, world: &impl Hittable, r_in: &Ray, rec: &HitRecord) -> Color {
+        let falloff = self.falloff(rec.p);
+        if falloff <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        falloff * PointLight::new(self.position, self.intensity).contribution(world, r_in, rec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::SolidBackground;
+    use crate::material::{DiffuseLight, Lambertian};
+    use crate::plane::Plane;
+    use crate::render::RenderStats;
+    use crate::tolerance;
+    use crate::util::seed_rng;
+    use crate::vec3::{COLOR_BLACK, COLOR_WHITE};
+    use crate::{HitList, PathContext};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sphere_sampling() {
+        seed_rng(2);
+        let sphere = Sphere::new(
+            Point3::new(0.0, 0.0, -4.0),
+            1.0,
+            Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0))),
+        );
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        // the cone's half-angle has sin = 1/4
+        let expected_pdf = 1.0 / (2.0 * PI * (1.0 - Float::sqrt(15.0 / 16.0)));
+
+        for _ in 0..100 {
+            let dir = sphere.random_to(origin);
+            assert!((sphere.pdf_value(origin, dir) - expected_pdf).abs() < 1e-9);
+        }
+        assert_eq!(sphere.pdf_value(origin, Vec3::new(0.0, 1.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn test_point_light_falls_off_with_distance_squared() {
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(5.0, 0.5, 0.0),
+            0.2,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )));
+
+        // direct light only, at the floor point straight below `position`
+        let direct = |point_lights: &[PointLight], x: Float| {
+            let ctx = PathContext {
+                world: &world,
+                lights: &[],
+                point_lights,
+                spot_lights: &[],
+                area_lights: &[],
+                background: &SolidBackground(COLOR_BLACK),
+                strategy: LightStrategy::default(),
+                primary_depth: 1,
+                indirect_background_scale: 1.0,
+            };
+            let r = Ray::new(Point3::new(x, 3.0, 1.0), Vec3::new(0.0, -3.0, -1.0));
+            r.trace_path(&ctx, 1, None, &mut RenderStats::default()).y()
+        };
+        let near = PointLight::new(Point3::new(0.0, 1.0, 0.0), COLOR_WHITE);
+        let far = PointLight::new(Point3::new(0.0, 2.0, 0.0), COLOR_WHITE);
+
+        let near_light = direct(&[near], 0.0);
+        let far_light = direct(&[far], 0.0);
+        assert!(far_light > 0.0);
+        let ratio = near_light / far_light;
+        assert!((ratio - 4.0).abs() < 0.01, "near/far ratio {ratio}");
+
+        // lights sum
+        let both = direct(&[near, far], 0.0);
+        assert!((both - (near_light + far_light)).abs() < tolerance(1e-9));
+
+        // the sphere hangs between this floor point and the light above it
+        let blocked = PointLight::new(Point3::new(5.0, 1.0, 0.0), COLOR_WHITE);
+        assert_eq!(direct(&[blocked], 5.0), 0.0);
+    }
+
+    #[test]
+    fn test_spot_light_fades_across_cone_edge() {
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )));
+        // straight down from one unit up, lit fully out to 20 degrees and not past 40
+        let spot = SpotLight::new(
+            Point3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            COLOR_WHITE,
+            20.0,
+            40.0,
+        );
+        let point_light = PointLight::new(spot.position, spot.intensity);
+
+        // direct light at floor points further and further off the axis
+        let mut previous = 1.0;
+        for degrees in [0, 10, 20, 25, 30, 35, 40, 45, 60] {
+            let x = degrees_to_radians(degrees as Float).tan();
+            let r = Ray::new(Point3::new(x, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+            let rec = world.hit(&r, 0.001, INFINITY).unwrap();
+            let unshaped = point_light.contribution(&world, &r, &rec).y();
+            let fraction = spot.contribution(&world, &r, &rec).y() / unshaped;
+
+            match degrees {
+                0..=20 => assert!((fraction - 1.0).abs() < tolerance(1e-9)),
+                40.. => assert_eq!(fraction, 0.0, "lit at {degrees} degrees"),
+                _ => assert!(
+                    0.0 < fraction && fraction < previous,
+                    "{fraction} of the light at {degrees} degrees, {previous} before"
+                ),
+            }
+            // no sudden steps across the fade
+            assert!(
+                previous - fraction < 0.6,
+                "dropped to {fraction} at {degrees}"
+            );
+            previous = fraction;
+        }
+    }
+}
@@ -1,32 +1,40 @@
-use rayon::prelude::*;
 use std::env;
+use std::io;
 
 mod scene;
 
-use rand::Rng;
-use rt::vec3::{Color, Point3, Vec3, COLOR_BLACK};
+use rt::render::{write_ppm, Renderer};
+use rt::util::seed_rng;
+use rt::vec3::{Point3, Vec3};
 use rt::Camera;
+use rt::Float;
 
 fn main() {
     let mut max_depth: i32 = 50;
-    let mut samples_per_pixel = 500.0;
+    let mut samples_per_pixel: u32 = 500;
     let mut world = scene::simple_scene();
     if env::var("FAST_MODE").is_ok() {
         max_depth = 3;
-        samples_per_pixel = 40.0;
+        samples_per_pixel = 40;
     }
     if env::var("DETAIL_MODE").is_ok() {
         max_depth = 50;
-        samples_per_pixel = 500.0;
+        samples_per_pixel = 500;
     }
+    // Renders (and the random scene) are reproducible for a given seed.
+    let seed: u64 = env::var("SEED")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    seed_rng(seed);
     if env::var("RANDOM_SCENE").is_ok() {
         world = scene::random_scene();
     }
 
     // Image
     let aspect_ratio = 3.0 / 2.0;
-    let image_width: i64 = 1200;
-    let image_height: i64 = (image_width as f64 / aspect_ratio) as i64;
+    let image_width: u32 = 1200;
+    let image_height: u32 = (image_width as Float / aspect_ratio) as u32;
 
     let max_depth = max_depth;
 
@@ -49,54 +57,21 @@ fn main() {
 
     // Render
 
-    // colors are in ascii
-    println!("P3");
-
-    // columns, rows
-    println!("{} {}", image_width, image_height);
-
-    // max color
-    println!("255");
-
-    // RGB triplets
-    for j in (0..image_height).rev() {
-        eprintln!("Lines remaining: {j}...");
-        let out: Vec<Color> = (0..image_width)
-            .into_par_iter()
-            .map(|i| {
-                let mut pixel_color = COLOR_BLACK;
-                for _ in 0..samples_per_pixel as i64 {
-                    let mut rng = rand::thread_rng();
-                    let u = (i as f64 + rng.gen::<f64>()) / (image_width as f64 - 1.0); // how horizontal? (0 to 1)
-                    let v = (j as f64 + rng.gen::<f64>()) / (image_height as f64 - 1.0); // how vertical? (0 to 1)
-                    let ray = camera.get_ray(u, v);
-                    pixel_color = pixel_color + ray.color(&world, max_depth);
-                }
-                pixel_color
-                // }
-            })
-            .collect();
-        for pixel_color in out {
-            write_color(pixel_color, samples_per_pixel);
-        }
-    }
+    let renderer = Renderer {
+        samples_per_pixel,
+        max_depth,
+        seed,
+        ..Renderer::new(image_width, image_height)
+    };
+    let pixels = renderer.render_parallel(&world, &camera);
+
+    write_ppm(
+        &mut io::stdout().lock(),
+        &pixels,
+        image_width,
+        image_height,
+        samples_per_pixel,
+    )
+    .expect("failed to write image");
     eprintln!("Done.");
 }
-
-fn write_color(color: Color, samples_per_pixel: f64) {
-    let mut r = color.x();
-    let mut g = color.y();
-    let mut b = color.z();
-
-    // Divide the color by the number of samples and gamma-correct for gamma=2.0.
-    let scale = 1.0 / samples_per_pixel;
-    r = f64::sqrt(scale * r);
-    g = f64::sqrt(scale * g);
-    b = f64::sqrt(scale * b);
-
-    let ir = (256.0 * rt::clamp(r, 0.0, 0.999)) as i64;
-    let ig = (256.0 * rt::clamp(g, 0.0, 0.999)) as i64;
-    let ib = (256.0 * rt::clamp(b, 0.0, 0.999)) as i64;
-
-    println!("{ir} {ig} {ib}");
-}
@@ -0,0 +1,149 @@
+use std::ops::Mul;
+
+use crate::util::degrees_to_radians;
+use crate::vec3::{dot, Vec3};
+use crate::Float;
+
+// A 3x3 matrix, for rotating directions and points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    rows: [Vec3; 3],
+}
+
+impl Mat3 {
+    pub fn from_rows(r0: Vec3, r1: Vec3, r2: Vec3) -> Mat3 {
+        Mat3 { rows: [r0, r1, r2] }
+    }
+
+    pub fn identity() -> Mat3 {
+        Mat3::from_rows(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    // Right-handed rotation about the x axis.
+    pub fn rotation_x(angle_degrees: Float) -> Mat3 {
+        let (sin, cos) = degrees_to_radians(angle_degrees).sin_cos();
+        Mat3::from_rows(
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, cos, -sin),
+            Vec3::new(0.0, sin, cos),
+        )
+    }
+
+    // Right-handed rotation about the y axis.
+    pub fn rotation_y(angle_degrees: Float) -> Mat3 {
+        let (sin, cos) = degrees_to_radians(angle_degrees).sin_cos();
+        Mat3::from_rows(
+            Vec3::new(cos, 0.0, sin),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(-sin, 0.0, cos),
+        )
+    }
+
+    // Right-handed rotation about the z axis.
+    pub fn rotation_z(angle_degrees: Float) -> Mat3 {
+        let (sin, cos) = degrees_to_radians(angle_degrees).sin_cos();
+        Mat3::from_rows(
+            Vec3::new(cos, -sin, 0.0),
+            Vec3::new(sin, cos, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        )
+    }
+
+    // Rotates about x, then y, then z.
+    pub fn from_euler(x_degrees: Float, y_degrees: Float, z_degrees: Float) -> Mat3 {
+        Mat3::rotation_z(z_degrees) * Mat3::rotation_y(y_degrees) * Mat3::rotation_x(x_degrees)
+    }
+
+    // Right-handed rotation about `axis` (Rodrigues' formula).
+    pub fn from_axis_angle(axis: Vec3, angle_degrees: Float) -> Mat3 {
+        let (sin, cos) = degrees_to_radians(angle_degrees).sin_cos();
+        let a = axis.unit_vector();
+        let (x, y, z) = (a.x(), a.y(), a.z());
+        let t = 1.0 - cos;
+        Mat3::from_rows(
+            Vec3::new(t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y),
+            Vec3::new(t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x),
+            Vec3::new(t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos),
+        )
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        let [r0, r1, r2] = self.rows;
+        Mat3::from_rows(
+            Vec3::new(r0.x(), r1.x(), r2.x()),
+            Vec3::new(r0.y(), r1.y(), r2.y()),
+            Vec3::new(r0.z(), r1.z(), r2.z()),
+        )
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            dot(self.rows[0], v),
+            dot(self.rows[1], v),
+            dot(self.rows[2], v),
+        )
+    }
+}
+
+impl Mul<Mat3> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        let columns = other.transpose();
+        let row = |r: Vec3| columns * r;
+        Mat3::from_rows(row(self.rows[0]), row(self.rows[1]), row(self.rows[2]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tolerance;
+
+    fn assert_mat_near(actual: Mat3, expected: Mat3) {
+        for i in 0..3 {
+            assert!(
+                (actual.rows[i] - expected.rows[i]).length() < tolerance(1e-9),
+                "expected {expected:?}, got {actual:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotations_turn_axes() {
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        let z = Vec3::new(0.0, 0.0, 1.0);
+        assert!((Mat3::rotation_x(90.0) * y - z).length() < tolerance(1e-9));
+        assert!((Mat3::rotation_y(90.0) * z - x).length() < tolerance(1e-9));
+        assert!((Mat3::rotation_z(90.0) * x - y).length() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_axis_angle_matches_axis_rotations() {
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        assert_mat_near(Mat3::from_axis_angle(x, 30.0), Mat3::rotation_x(30.0));
+        assert_mat_near(
+            Mat3::from_axis_angle(Vec3::new(0.0, 0.0, 2.0), -75.0),
+            Mat3::rotation_z(-75.0),
+        );
+        assert_mat_near(
+            Mat3::from_euler(0.0, 40.0, 0.0),
+            Mat3::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 40.0),
+        );
+    }
+
+    #[test]
+    fn test_transpose_inverts_rotation() {
+        let m = Mat3::from_axis_angle(Vec3::new(1.0, 2.0, 3.0), 50.0);
+        assert_mat_near(m * m.transpose(), Mat3::identity());
+    }
+}
@@ -0,0 +1,155 @@
+use std::ops::Mul;
+
+use crate::mat3::Mat3;
+use crate::vec3::{Point3, Vec3};
+use crate::Float;
+
+// A 4x4 matrix for affine transforms of points and directions (the bottom row is
+// normally 0, 0, 0, 1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    rows: [[Float; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn from_rows(rows: [[Float; 4]; 4]) -> Mat4 {
+        Mat4 { rows }
+    }
+
+    pub fn identity() -> Mat4 {
+        Mat4::scaling(Vec3::new(1.0, 1.0, 1.0))
+    }
+
+    pub fn translation(offset: Vec3) -> Mat4 {
+        Mat4::from_rows([
+            [1.0, 0.0, 0.0, offset.x()],
+            [0.0, 1.0, 0.0, offset.y()],
+            [0.0, 0.0, 1.0, offset.z()],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // Scales each axis by the matching component of `factors`.
+    pub fn scaling(factors: Vec3) -> Mat4 {
+        Mat4::from_rows([
+            [factors.x(), 0.0, 0.0, 0.0],
+            [0.0, factors.y(), 0.0, 0.0],
+            [0.0, 0.0, factors.z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // The linear part `m`, with no translation.
+    pub fn from_mat3(m: Mat3) -> Mat4 {
+        let columns = [
+            m * Vec3::new(1.0, 0.0, 0.0),
+            m * Vec3::new(0.0, 1.0, 0.0),
+            m * Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let mut rows = Mat4::identity().rows;
+        for (r, row) in rows.iter_mut().take(3).enumerate() {
+            for (c, column) in columns.iter().enumerate() {
+                row[c] = column[r];
+            }
+        }
+        Mat4::from_rows(rows)
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let mut rows = [[0.0; 4]; 4];
+        for (r, row) in rows.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = self.rows[c][r];
+            }
+        }
+        Mat4::from_rows(rows)
+    }
+
+    // Gauss-Jordan elimination; None if the matrix is singular.
+    pub fn inverse(&self) -> Option<Mat4> {
+        let mut a = self.rows;
+        let mut inv = Mat4::identity().rows;
+        for col in 0..4 {
+            // swap in the row with the largest pivot, for stability
+            let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+            if a[pivot][col].abs() < Float::EPSILON {
+                return None;
+            }
+            a.swap(col, pivot);
+            inv.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for c in 0..4 {
+                a[col][c] *= scale;
+                inv[col][c] *= scale;
+            }
+            for r in (0..4).filter(|&r| r != col) {
+                let factor = a[r][col];
+                for c in 0..4 {
+                    a[r][c] -= factor * a[col][c];
+                    inv[r][c] -= factor * inv[col][c];
+                }
+            }
+        }
+        Some(Mat4::from_rows(inv))
+    }
+
+    pub fn transform_point(&self, p: Point3) -> Point3 {
+        self.transform_vector(p) + Vec3::new(self.rows[0][3], self.rows[1][3], self.rows[2][3])
+    }
+
+    // Directions ignore the translation.
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let row = |r: usize| {
+            let m = self.rows[r];
+            m[0] * v.x() + m[1] * v.y() + m[2] * v.z()
+        };
+        Vec3::new(row(0), row(1), row(2))
+    }
+}
+
+impl Mul<Mat4> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let mut rows = [[0.0; 4]; 4];
+        for (r, row) in rows.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.rows[r][k] * other.rows[k][c]).sum();
+            }
+        }
+        Mat4::from_rows(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tolerance;
+
+    #[test]
+    fn test_inverse_undoes_transform() {
+        let shear = Mat4::from_rows([
+            [1.0, 0.5, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        let m = Mat4::translation(Vec3::new(1.0, -2.0, 3.0))
+            * Mat4::from_mat3(Mat3::from_axis_angle(Vec3::new(1.0, 1.0, 0.0), 35.0))
+            * shear
+            * Mat4::scaling(Vec3::new(2.0, 0.5, 3.0));
+        let inverse = m.inverse().unwrap();
+        let p = Point3::new(0.3, -1.7, 2.2);
+        let round_trip = inverse.transform_point(m.transform_point(p));
+        assert!((round_trip - p).length() < tolerance(1e-9));
+        for r in 0..4 {
+            for c in 0..4 {
+                let expected = if r == c { 1.0 } else { 0.0 };
+                assert!(((m * inverse).rows[r][c] - expected).abs() < tolerance(1e-9));
+            }
+        }
+
+        assert!(Mat4::scaling(Vec3::new(1.0, 0.0, 1.0)).inverse().is_none());
+    }
+}
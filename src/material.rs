@@ -1,49 +1,197 @@
-use crate::{dot, util::random_double, Color, HitRecord, Ray, Vec3, COLOR_WHITE};
+use std::sync::Arc;
+
+use crate::onb::Onb;
+use crate::pdf::{CosinePdf, Pdf};
+use crate::texture::{SolidColor, Texture};
+use crate::vec3::{COLOR_BLACK, COLOR_WHITE};
+use crate::Float;
+use crate::{dot, util::random_double, Color, HitRecord, Point3, Ray, Vec3};
 
 pub struct ScatterResult {
     pub scattered: Ray,
     pub attenuation: Color,
+    // chosen by a mirror-like lobe with no density (a perfect mirror or a refraction), so
+    // paths follow `attenuation` rather than weighting by `scattering_pdf`
+    pub is_specular: bool,
 }
 
 pub trait Material: Send + Sync {
     fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult>;
+
+    // Light given off by the surface itself, toward a ray that hit its front (outside)
+    // or back. Most materials don't emit any.
+    fn emitted(&self, _u: Float, _v: Float, _p: Point3, _front_face: bool) -> Color {
+        COLOR_BLACK
+    }
+
+    // A representative surface color, for albedo buffers and previews, without scattering.
+    fn albedo(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        COLOR_BLACK
+    }
+
+    // Probability density of `scatter` choosing `scattered`, with the convention that
+    // BRDF * cos(theta) == albedo * pdf. None for materials that can't be sampled
+    // toward a light (mirrors, glass, lights).
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> Option<Float> {
+        None
+    }
+
+    // BRDF * cos(theta) for light leaving along `scattered`, for materials with a
+    // `scattering_pdf`. The default assumes the albedo * pdf convention holds.
+    fn brdf_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        let pdf = self.scattering_pdf(r_in, rec, scattered).unwrap_or(0.0);
+        self.albedo(rec.u, rec.v, rec.p) * pdf
+    }
 }
 
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
+    // sample a true cosine lobe, rather than `normal + random_unit_vector`
+    cosine_weighted: bool,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
-        Self { albedo }
+        Self::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self {
+            albedo,
+            cosine_weighted: true,
+        }
+    }
+
+    // Switches between cosine-weighted sampling (the default) and the
+    // original `normal + random_unit_vector` approximation, for comparison.
+    pub fn cosine_weighted(self, cosine_weighted: bool) -> Self {
+        Self {
+            cosine_weighted,
+            ..self
+        }
     }
 }
 
 impl Material for Lambertian {
-    fn scatter(&self, _r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
         // eprintln!("scatter Lambertian");
-        let random_scatter_direction = rec.normal + Vec3::new_random_unit_vector();
-        let scatter_direction = if random_scatter_direction.near_zero() {
-            rec.normal
+        let scatter_direction = if self.cosine_weighted {
+            Onb::build_from_w(rec.normal).local_vec(Vec3::random_cosine_direction())
         } else {
-            random_scatter_direction
+            let random_scatter_direction = rec.normal + Vec3::new_random_unit_vector();
+            if random_scatter_direction.near_zero() {
+                rec.normal
+            } else {
+                random_scatter_direction
+            }
         };
 
         Some(ScatterResult {
-            scattered: Ray::new(rec.p, scatter_direction),
-            attenuation: self.albedo,
+            scattered: Ray::with_time(rec.p, scatter_direction, r.time),
+            attenuation: self.albedo.value(rec.u, rec.v, rec.p),
+            is_specular: false,
         })
     }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.albedo.value(u, v, p)
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Float> {
+        Some(CosinePdf::new(rec.normal).value(scattered.dir))
+    }
+}
+
+// A rough matte surface (clay, the moon), using the Oren–Nayar model.
+// Rough surfaces scatter more light back toward its source than a Lambertian one does.
+pub struct OrenNayar {
+    albedo: Arc<dyn Texture>,
+    // precomputed from the roughness (sigma, the standard deviation of facet angles, in radians)
+    a: Float,
+    b: Float,
+}
+
+impl OrenNayar {
+    pub fn new(albedo: Color, roughness: Float) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)), roughness)
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>, roughness: Float) -> Self {
+        let sigma2 = roughness * roughness;
+        Self {
+            albedo,
+            a: 1.0 - 0.5 * sigma2 / (sigma2 + 0.33),
+            b: 0.45 * sigma2 / (sigma2 + 0.09),
+        }
+    }
+
+    // The Oren–Nayar BRDF relative to the Lambertian one, for light arriving from `wi`
+    // and leaving toward `wo` (both pointing away from the surface).
+    fn factor(&self, normal: Vec3, wi: Vec3, wo: Vec3) -> Float {
+        let cos_i = dot(normal, wi).clamp(-1.0, 1.0);
+        let cos_o = dot(normal, wo).clamp(-1.0, 1.0);
+        let (theta_i, theta_o) = (cos_i.acos(), cos_o.acos());
+        let alpha = theta_i.max(theta_o);
+        let beta = theta_i.min(theta_o);
+
+        // cosine of the azimuthal angle between the two directions
+        let cos_phi = match (
+            (wi - cos_i * normal).try_unit_vector(),
+            (wo - cos_o * normal).try_unit_vector(),
+        ) {
+            (Some(a), Some(b)) => dot(a, b),
+            _ => 0.0,
+        };
+        self.a + self.b * cos_phi.max(0.0) * alpha.sin() * beta.tan()
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        // cosine-weighted, like Lambertian, with the difference folded into the attenuation
+        let direction = Onb::build_from_w(rec.normal).local_vec(Vec3::random_cosine_direction());
+        let factor = self.factor(rec.normal, -r.dir.unit_vector(), direction.unit_vector());
+        Some(ScatterResult {
+            scattered: Ray::with_time(rec.p, direction, r.time),
+            attenuation: self.albedo.value(rec.u, rec.v, rec.p) * factor,
+            is_specular: false,
+        })
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Float> {
+        Some(CosinePdf::new(rec.normal).value(scattered.dir))
+    }
+
+    fn brdf_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        let cosine_pdf = CosinePdf::new(rec.normal).value(scattered.dir);
+        let factor = self.factor(
+            rec.normal,
+            -r_in.dir.unit_vector(),
+            scattered.dir.unit_vector(),
+        );
+        self.albedo.value(rec.u, rec.v, rec.p) * factor * cosine_pdf
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.albedo.value(u, v, p)
+    }
 }
 
 pub struct Metal {
     albedo: Color,
-    fuzz: f64,
+    fuzz: Float,
 }
 
 impl Metal {
-    pub fn new(albedo: Color, fuzz: f64) -> Self {
-        Self { albedo, fuzz }
+    pub fn new(albedo: Color, fuzz: Float) -> Self {
+        Self {
+            albedo,
+            fuzz: fuzz.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn fuzz(&self) -> Float {
+        self.fuzz
     }
 }
 
@@ -52,19 +200,179 @@ impl Material for Metal {
         // eprintln!("scatter Metal");
         let reflected = reflect(r.dir.unit_vector(), rec.normal);
 
-        let scattered = Ray::new(
+        let scattered = Ray::with_time(
             rec.p,
             reflected + Vec3::new_random_in_unit_sphere() * self.fuzz,
+            r.time,
         );
         if dot(scattered.dir, rec.normal) > 0.0 {
             Some(ScatterResult {
                 scattered,
                 attenuation: self.albedo,
+                is_specular: self.fuzz == 0.0,
             })
         } else {
             None
         }
     }
+
+    fn albedo(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        self.albedo
+    }
+}
+
+// A rough metal using the Cook–Torrance model with a GGX (Trowbridge–Reitz) distribution.
+pub struct GgxMetal {
+    albedo: Color,
+    roughness: Float,
+}
+
+impl GgxMetal {
+    pub fn new(albedo: Color, roughness: Float) -> Self {
+        Self {
+            albedo,
+            roughness: roughness.clamp(0.0, 1.0),
+        }
+    }
+
+    // Samples a microfacet normal, importance sampling the GGX distribution around `normal`.
+    fn sample_half_vector(&self, normal: Vec3) -> Vec3 {
+        let alpha2 = self.roughness.powi(4);
+        let r1 = random_double();
+        let r2 = random_double();
+        let cos_theta = Float::sqrt((1.0 - r2) / (1.0 + (alpha2 - 1.0) * r2)).min(1.0);
+        let sin_theta = Float::sqrt(1.0 - cos_theta * cos_theta);
+        let phi = 2.0 * crate::consts::PI * r1;
+        Onb::build_from_w(normal).local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+    }
+
+    // Smith masking term for one direction.
+    fn smith_g1(&self, n_dot_x: Float) -> Float {
+        let alpha2 = self.roughness.powi(4);
+        2.0 * n_dot_x / (n_dot_x + Float::sqrt(alpha2 + (1.0 - alpha2) * n_dot_x * n_dot_x))
+    }
+}
+
+impl Material for GgxMetal {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        let unit_direction = r.dir.unit_vector();
+        let h = self.sample_half_vector(rec.normal);
+        let reflected = reflect(unit_direction, h);
+
+        let view = -unit_direction;
+        let n_dot_v = dot(rec.normal, view);
+        let n_dot_l = dot(rec.normal, reflected);
+        let n_dot_h = dot(rec.normal, h);
+        let v_dot_h = dot(view, h);
+        // reflected below the surface
+        if n_dot_l <= 0.0 || n_dot_v <= 0.0 || v_dot_h <= 0.0 {
+            return None;
+        }
+
+        // Schlick's Fresnel, with the albedo as the reflectance at normal incidence
+        let fresnel = self.albedo + (COLOR_WHITE - self.albedo) * (1.0 - v_dot_h).powi(5);
+        let geometry = self.smith_g1(n_dot_v) * self.smith_g1(n_dot_l);
+        // BRDF * cos / pdf, where the distribution term cancels with the sampling pdf
+        let weight = geometry * v_dot_h / (n_dot_v * n_dot_h);
+
+        Some(ScatterResult {
+            scattered: Ray::with_time(rec.p, reflected, r.time),
+            attenuation: fresnel * weight,
+            is_specular: false,
+        })
+    }
+
+    fn albedo(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        self.albedo
+    }
+}
+
+// A classic (non-physically based) mix of a Lambertian bounce and a Phong specular lobe.
+pub struct Phong {
+    diffuse: Color,
+    specular: Color,
+    shininess: Float,
+}
+
+impl Phong {
+    pub fn new(diffuse: Color, specular: Color, shininess: Float) -> Self {
+        Self {
+            diffuse,
+            specular,
+            shininess: shininess.max(0.0),
+        }
+    }
+
+    // Density of the specular lobe around the mirror direction of `r_in`.
+    fn specular_lobe(&self, r_in: &Ray, rec: &HitRecord, direction: Vec3) -> Float {
+        let reflected = reflect(r_in.dir.unit_vector(), rec.normal);
+        let cos_alpha = Float::max(dot(reflected, direction.unit_vector()), 0.0);
+        (self.shininess + 1.0) / (2.0 * crate::consts::PI) * cos_alpha.powf(self.shininess)
+    }
+
+    // Probability of sampling the specular lobe, from the relative weights of the two lobes.
+    fn specular_probability(&self) -> Float {
+        let weight = |c: Color| (c.x() + c.y() + c.z()) / 3.0;
+        let total = weight(self.diffuse) + weight(self.specular);
+        if total <= 0.0 {
+            0.0
+        } else {
+            weight(self.specular) / total
+        }
+    }
+}
+
+impl Material for Phong {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        let p_specular = self.specular_probability();
+        let (direction, attenuation) = if random_double() < p_specular {
+            // power-cosine lobe around the mirror direction
+            let reflected = reflect(r.dir.unit_vector(), rec.normal);
+            let cos_theta = random_double().powf(1.0 / (self.shininess + 1.0));
+            let sin_theta = Float::sqrt(1.0 - cos_theta * cos_theta);
+            let phi = 2.0 * crate::consts::PI * random_double();
+            let direction = Onb::build_from_w(reflected).local(
+                sin_theta * phi.cos(),
+                sin_theta * phi.sin(),
+                cos_theta,
+            );
+            (direction, self.specular / p_specular)
+        } else {
+            let direction =
+                Onb::build_from_w(rec.normal).local_vec(Vec3::random_cosine_direction());
+            (direction, self.diffuse / (1.0 - p_specular))
+        };
+
+        // sampled below the surface
+        if dot(direction, rec.normal) <= 0.0 {
+            return None;
+        }
+        Some(ScatterResult {
+            scattered: Ray::with_time(rec.p, direction, r.time),
+            attenuation,
+            is_specular: false,
+        })
+    }
+
+    fn albedo(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        self.diffuse
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Float> {
+        let p_specular = self.specular_probability();
+        let diffuse = CosinePdf::new(rec.normal).value(scattered.dir);
+        let specular = self.specular_lobe(r_in, rec, scattered.dir);
+        Some(p_specular * specular + (1.0 - p_specular) * diffuse)
+    }
+
+    fn brdf_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        if dot(scattered.dir, rec.normal) <= 0.0 {
+            return COLOR_BLACK;
+        }
+        let diffuse = CosinePdf::new(rec.normal).value(scattered.dir);
+        let specular = self.specular_lobe(r_in, rec, scattered.dir);
+        self.diffuse * diffuse + self.specular * specular
+    }
 }
 
 pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
@@ -72,13 +380,25 @@ pub fn reflect(v: Vec3, n: Vec3) -> Vec3 {
 }
 
 pub struct Dialectric {
-    index_of_refraction: f64,
+    index_of_refraction: Float,
+    // per-channel Beer–Lambert absorption coefficients; zero for clear glass
+    absorption: Color,
 }
 
 impl Dialectric {
-    pub fn new(index_of_refraction: f64) -> Self {
+    pub fn new(index_of_refraction: Float) -> Self {
         Self {
             index_of_refraction,
+            absorption: COLOR_BLACK,
+        }
+    }
+
+    // Colored glass: light travelling a distance d inside is attenuated by
+    // exp(-absorption * (1 - attenuation_color) * d) per channel.
+    pub fn with_absorption(self, attenuation_color: Color, absorption: Float) -> Self {
+        Self {
+            absorption: absorption * (COLOR_WHITE - attenuation_color),
+            ..self
         }
     }
 }
@@ -93,44 +413,324 @@ impl Material for Dialectric {
 
         let unit_direction = r_in.dir.unit_vector();
 
-        let cos_theta = f64::min(dot(-unit_direction, rec.normal), 1.0);
-        let sin_theta = f64::sqrt(1.0 - cos_theta * cos_theta);
+        let cos_theta = Float::min(dot(-unit_direction, rec.normal), 1.0);
 
-        let cannot_refract = refraction_ratio * sin_theta > 1.0;
+        // total internal reflection leaves no refracted ray, so the ray must reflect
+        let direction = match try_refract(unit_direction, rec.normal, refraction_ratio) {
+            Some(refracted) if reflectance(cos_theta, refraction_ratio) <= random_double() => {
+                refracted
+            }
+            _ => reflect(unit_direction, rec.normal),
+        };
 
-        let direction =
-            if cannot_refract || reflectance(cos_theta, refraction_ratio) > random_double() {
-                reflect(unit_direction, rec.normal)
-            } else {
-                refract(unit_direction, rec.normal, refraction_ratio)
-            };
+        // Hitting a back face means the ray has travelled through the medium to get here.
+        let attenuation = if rec.front_face {
+            COLOR_WHITE
+        } else {
+            let distance = rec.t * r_in.dir.length();
+            self.absorption.map(|a| (-a * distance).exp())
+        };
 
-        let scattered = Ray::new(rec.p, direction);
+        let scattered = Ray::with_time(rec.p, direction, r_in.time);
         Some(ScatterResult {
             scattered,
-            attenuation: COLOR_WHITE,
+            attenuation,
+            is_specular: true,
         })
     }
+
+    fn albedo(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        COLOR_WHITE
+    }
+}
+
+// Bends the unit direction `uv` through a surface with normal `n` by Snell's law. None
+// past the critical angle, where everything is reflected.
+pub fn try_refract(uv: Vec3, n: Vec3, etai_over_etat: Float) -> Option<Vec3> {
+    let (r_out_perp, parallel_squared) = refract_parts(uv, n, etai_over_etat);
+    if parallel_squared < 0.0 {
+        return None;
+    }
+    Some(r_out_perp - parallel_squared.sqrt() * n)
+}
+
+// Like `try_refract`, but never fails: past the critical angle the result isn't a real
+// refraction, so callers should check for that themselves.
+pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: Float) -> Vec3 {
+    let (r_out_perp, parallel_squared) = refract_parts(uv, n, etai_over_etat);
+    r_out_perp - parallel_squared.abs().sqrt() * n
 }
 
-pub fn refract(uv: Vec3, n: Vec3, etai_over_etat: f64) -> Vec3 {
+// The refracted ray's part perpendicular to `n`, and the squared length left over for
+// the parallel part (negative under total internal reflection).
+fn refract_parts(uv: Vec3, n: Vec3, etai_over_etat: Float) -> (Vec3, Float) {
     let cos_theta = ((-1.0) * uv).dot(n).min(1.0);
     let r_out_perp = etai_over_etat * (uv + cos_theta * n);
-    let r_out_parallel = -(1.0 - r_out_perp.length().powi(2)).abs().sqrt() * n;
-    r_out_perp + r_out_parallel
+    (r_out_perp, 1.0 - r_out_perp.length().powi(2))
 }
 
-fn reflectance(cos_theta: f64, refraction_ratio: f64) -> f64 {
+fn reflectance(cos_theta: Float, refraction_ratio: Float) -> Float {
     // Use Schlick's approximation for reflectance.
     let r0 = (1.0 - refraction_ratio) / (1.0 + refraction_ratio);
     let r0 = r0 * r0;
-    return r0 + (1.0 - r0) * (1.0 - cos_theta).powi(5);
+    r0 + (1.0 - r0) * (1.0 - cos_theta).powi(5)
+}
+
+// A clear varnish over another material: light either glances off the coat, more so at
+// grazing angles, or passes through to scatter off the base.
+pub struct Coated {
+    base: Arc<dyn Material>,
+    clearcoat_ior: Float,
+}
+
+impl Coated {
+    pub fn new(base: Arc<dyn Material>, clearcoat_ior: Float) -> Self {
+        Self {
+            base,
+            clearcoat_ior,
+        }
+    }
+}
+
+impl Coated {
+    // The share of light arriving along `r_in` that glances off the coat.
+    fn coat_reflectance(&self, r_in: &Ray, rec: &HitRecord) -> Float {
+        let cos_theta = Float::min(dot(-r_in.dir.unit_vector(), rec.normal), 1.0);
+        reflectance(cos_theta, 1.0 / self.clearcoat_ior)
+    }
+}
+
+impl Material for Coated {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        if self.coat_reflectance(r_in, rec) > random_double() {
+            return Some(ScatterResult {
+                scattered: Ray::with_time(
+                    rec.p,
+                    reflect(r_in.dir.unit_vector(), rec.normal),
+                    r_in.time,
+                ),
+                attenuation: COLOR_WHITE,
+                is_specular: true,
+            });
+        }
+        self.base.scatter(r_in, rec)
+    }
+
+    // Only the light that gets through the coat reaches the base. The coat's own mirror
+    // bounce is flagged `is_specular` by `scatter`, and has no density here.
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Float> {
+        let pdf = self.base.scattering_pdf(r_in, rec, scattered)?;
+        Some((1.0 - self.coat_reflectance(r_in, rec)) * pdf)
+    }
+
+    fn brdf_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        (1.0 - self.coat_reflectance(r_in, rec)) * self.base.brdf_cos(r_in, rec, scattered)
+    }
+
+    fn emitted(&self, u: Float, v: Float, p: Point3, front_face: bool) -> Color {
+        self.base.emitted(u, v, p, front_face)
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.base.albedo(u, v, p)
+    }
+}
+
+// A light source: emits light and doesn't scatter any.
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+    // whether the back of the surface glows too
+    two_sided: bool,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    // Emits a pattern, like a textured screen or a patterned light panel.
+    pub fn with_texture(emit: Arc<dyn Texture>) -> Self {
+        Self {
+            emit,
+            two_sided: true,
+        }
+    }
+
+    // Lights emit from both sides unless this is false, when only the front glows.
+    pub fn with_two_sided(self, two_sided: bool) -> Self {
+        Self { two_sided, ..self }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _r: &Ray, _rec: &HitRecord) -> Option<ScatterResult> {
+        None
+    }
+
+    fn emitted(&self, u: Float, v: Float, p: Point3, front_face: bool) -> Color {
+        if !front_face && !self.two_sided {
+            return COLOR_BLACK;
+        }
+        self.emit.value(u, v, p)
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.emit.value(u, v, p)
+    }
+}
+
+// Scatters uniformly in all directions; the phase function of an isotropic volume.
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        Some(ScatterResult {
+            scattered: Ray::with_time(rec.p, Vec3::new_random_unit_vector(), r.time),
+            attenuation: self.albedo.value(rec.u, rec.v, rec.p),
+            is_specular: false,
+        })
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.albedo.value(u, v, p)
+    }
+}
+
+// A phase function for participating media that prefers scattering forward (g > 0) or
+// back (g < 0) along the incoming ray, following Henyey-Greenstein. g = 0 is isotropic.
+pub struct Anisotropic {
+    albedo: Arc<dyn Texture>,
+    // mean cosine of the scattering angle, in (-1, 1)
+    g: Float,
+}
+
+impl Anisotropic {
+    pub fn new(albedo: Color, g: Float) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)), g)
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>, g: Float) -> Self {
+        Self {
+            albedo,
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+
+    // Cosine of the angle between the incoming and scattered directions.
+    fn sample_cos_theta(&self) -> Float {
+        let xi = random_double();
+        let g = self.g;
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * xi;
+        }
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        ((1.0 + g * g - s * s) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for Anisotropic {
+    fn scatter(&self, r: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        let cos_theta = self.sample_cos_theta();
+        let sin_theta = Float::sqrt(1.0 - cos_theta * cos_theta);
+        let phi = 2.0 * crate::consts::PI * random_double();
+        let direction =
+            Onb::build_from_w(r.dir).local(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+        Some(ScatterResult {
+            scattered: Ray::with_time(rec.p, direction, r.time),
+            attenuation: self.albedo.value(rec.u, rec.v, rec.p),
+            is_specular: false,
+        })
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.albedo.value(u, v, p)
+    }
+}
+
+// Adds surface detail to another material by tilting the shading normal to match a
+// tangent-space normal map, such as `texture::NormalMap`.
+pub struct Bumped {
+    inner: Arc<dyn Material>,
+    map: Arc<dyn Texture>,
+}
+
+impl Bumped {
+    pub fn new(inner: Arc<dyn Material>, map: Arc<dyn Texture>) -> Self {
+        Self { inner, map }
+    }
+
+    // `rec` with its normal replaced by the mapped one.
+    fn perturbed(&self, rec: &HitRecord) -> HitRecord {
+        let n = rec.normal;
+        // Gram-Schmidt the primitive's tangent against the normal, or make one up
+        let t = (rec.tangent - dot(rec.tangent, n) * n)
+            .try_unit_vector()
+            .unwrap_or_else(|| Onb::build_from_w(n).u);
+        let b = n.cross(t);
+
+        let encoded = self.map.value(rec.u, rec.v, rec.p);
+        let local = 2.0 * encoded - COLOR_WHITE;
+        let normal = (local.x() * t + local.y() * b + local.z() * n)
+            .try_unit_vector()
+            .unwrap_or(n);
+        HitRecord {
+            p: rec.p,
+            normal,
+            tangent: t,
+            t: rec.t,
+            u: rec.u,
+            v: rec.v,
+            front_face: rec.front_face,
+            mat_ptr: Arc::clone(&rec.mat_ptr),
+            obj_id: rec.obj_id,
+        }
+    }
+}
+
+impl Material for Bumped {
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterResult> {
+        self.inner.scatter(r_in, &self.perturbed(rec))
+    }
+
+    fn emitted(&self, u: Float, v: Float, p: Point3, front_face: bool) -> Color {
+        self.inner.emitted(u, v, p, front_face)
+    }
+
+    fn albedo(&self, u: Float, v: Float, p: Point3) -> Color {
+        self.inner.albedo(u, v, p)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Option<Float> {
+        self.inner
+            .scattering_pdf(r_in, &self.perturbed(rec), scattered)
+    }
+
+    fn brdf_cos(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> Color {
+        self.inner.brdf_cos(r_in, &self.perturbed(rec), scattered)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use crate::background::SolidBackground;
+    use crate::light::PointLight;
+    use crate::quad::Quad;
+    use crate::texture::UvChecker;
+    use crate::{tolerance, HitList, Hittable, INFINITY};
 
     #[test]
     fn test_reflect() {
@@ -145,7 +745,427 @@ mod tests {
         let normal = Vec3::new(0.0, 1.0, 0.0);
         assert_eq!(
             refract(v, normal, 1.5),
-            Vec3::new(1.5, -1.118033988749895, 0.0)
+            Vec3::new(1.5, -Float::sqrt(1.25), 0.0)
+        );
+    }
+
+    #[test]
+    fn test_try_refract_follows_snells_law() {
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        let incoming = Vec3::new(1.0, -1.0, 0.0).unit_vector();
+        let refracted = try_refract(incoming, normal, 1.0 / 1.5).unwrap();
+        let sin_in = incoming.x();
+        let sin_out = refracted.x() / refracted.length();
+        assert!((sin_in - 1.5 * sin_out).abs() < tolerance(1e-9));
+        assert!((refracted.length() - 1.0).abs() < tolerance(1e-9));
+        assert!(refracted.y() < 0.0);
+    }
+
+    #[test]
+    fn test_try_refract_is_none_under_total_internal_reflection() {
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        // 45° inside glass is past the ~41.8° critical angle
+        let incoming = Vec3::new(1.0, -1.0, 0.0).unit_vector();
+        assert_eq!(try_refract(incoming, normal, 1.5), None);
+    }
+
+    fn test_record(mat_ptr: Arc<dyn Material>) -> HitRecord {
+        HitRecord {
+            p: Point3::new(0.0, 0.0, 0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            front_face: true,
+            mat_ptr,
+            obj_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_diffuse_light_emits_its_texture() {
+        let (warm, cool) = (Color::new(4.0, 2.0, 1.0), Color::new(1.0, 2.0, 4.0));
+        // a 2 by 1 panel: warm on the left half, cool on the right
+        let panel = Quad::new(
+            Point3::new(-1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(DiffuseLight::with_texture(Arc::new(
+                UvChecker::from_colors(warm, cool, 2, 1),
+            ))),
+        );
+        for (x, expected) in [(-0.5, warm), (0.5, cool)] {
+            let r = Ray::new(Point3::new(x, 0.5, 1.0), Vec3::new(0.0, 0.0, -1.0));
+            let rec = panel.hit(&r, 0.001, INFINITY).unwrap();
+            assert_eq!(
+                rec.mat_ptr.emitted(rec.u, rec.v, rec.p, rec.front_face),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_one_sided_light_is_dark_from_behind() {
+        let glow = Color::new(3.0, 3.0, 3.0);
+        // facing +z
+        let panel = |light: DiffuseLight| {
+            Quad::new(
+                Point3::new(-1.0, -1.0, 0.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 2.0, 0.0),
+                Arc::new(light),
+            )
+        };
+        let front = || Ray::new(Point3::new(0.0, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0));
+        let back = || Ray::new(Point3::new(0.0, 0.0, -2.0), Vec3::new(0.0, 0.0, 1.0));
+        let dark = SolidBackground(COLOR_BLACK);
+
+        let one_sided = panel(DiffuseLight::new(glow).with_two_sided(false));
+        assert_eq!(front().color(&one_sided, &dark, 5), glow);
+        assert_eq!(back().color(&one_sided, &dark, 5), COLOR_BLACK);
+
+        let two_sided = panel(DiffuseLight::new(glow));
+        assert_eq!(back().color(&two_sided, &dark, 5), glow);
+    }
+
+    #[test]
+    fn test_lambertian_is_cosine_weighted() {
+        crate::util::seed_rng(1);
+        let lambertian = Lambertian::new(COLOR_WHITE);
+        let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+
+        let n = 5000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let out = lambertian.scatter(&r, &rec).unwrap();
+            let cos_theta = dot(out.scattered.dir.unit_vector(), rec.normal);
+            assert!(cos_theta >= 0.0);
+            sum += cos_theta;
+        }
+        let mean = sum / n as Float;
+        assert!((mean - 2.0 / 3.0).abs() < 0.02, "mean cos(theta) = {mean}");
+    }
+
+    #[test]
+    fn test_oren_nayar_smooth_matches_lambertian() {
+        crate::util::seed_rng(6);
+        let albedo = Color::new(0.6, 0.5, 0.4);
+        let matte = OrenNayar::new(albedo, 0.0);
+        let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+        let dir = Vec3::new(1.0, -0.3, 0.2);
+        let r = Ray::new(rec.p - dir, dir);
+        for _ in 0..100 {
+            let out = matte.scatter(&r, &rec).unwrap();
+            assert!((out.attenuation - albedo).length() < 1e-12);
+            assert!(dot(out.scattered.dir, rec.normal) >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_oren_nayar_roughness_increases_retroreflection() {
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        // light arriving 80 degrees from the normal
+        let theta = Float::to_radians(80.0);
+        let toward_light = Vec3::new(theta.sin(), theta.cos(), 0.0);
+        let forward = Vec3::new(-theta.sin(), theta.cos(), 0.0);
+
+        let smooth = OrenNayar::new(COLOR_WHITE, 0.0);
+        let rough = OrenNayar::new(COLOR_WHITE, 0.5);
+        assert_eq!(smooth.factor(normal, toward_light, toward_light), 1.0);
+        let retro = rough.factor(normal, toward_light, toward_light);
+        assert!(retro > 1.0, "retroreflection factor {retro}");
+        assert!(rough.factor(normal, toward_light, forward) < retro);
+    }
+
+    #[test]
+    fn test_albedo() {
+        let color = Color::new(0.1, 0.2, 0.3);
+        let p = Point3::new(0.0, 0.0, 0.0);
+        let materials: [(Box<dyn Material>, Color); 8] = [
+            (Box::new(Lambertian::new(color)), color),
+            (Box::new(OrenNayar::new(color, 0.3)), color),
+            (Box::new(Metal::new(color, 0.1)), color),
+            (Box::new(GgxMetal::new(color, 0.1)), color),
+            (Box::new(Phong::new(color, COLOR_WHITE, 10.0)), color),
+            (Box::new(Dialectric::new(1.5)), COLOR_WHITE),
+            (Box::new(DiffuseLight::new(color * 4.0)), color * 4.0),
+            (Box::new(Isotropic::new(color)), color),
+        ];
+        for (material, expected) in materials {
+            assert_eq!(material.albedo(0.5, 0.5, p), expected);
+        }
+    }
+
+    #[test]
+    fn test_metal_fuzz_is_clamped() {
+        assert_eq!(Metal::new(COLOR_WHITE, 0.3).fuzz(), 0.3);
+        assert_eq!(Metal::new(COLOR_WHITE, 2.0).fuzz(), 1.0);
+        assert_eq!(Metal::new(COLOR_WHITE, -0.5).fuzz(), 0.0);
+    }
+
+    #[test]
+    fn test_ggx_roughness_widens_reflection() {
+        crate::util::seed_rng(3);
+        let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+        let dir = Vec3::new(1.0, -1.0, 0.0);
+        let r = Ray::new(rec.p - dir, dir);
+        let mirror = reflect(dir.unit_vector(), rec.normal);
+
+        // average angle away from the mirror direction, over the rays that weren't absorbed
+        let mean_spread = |metal: &GgxMetal| {
+            let cosines: Vec<Float> = (0..2000)
+                .filter_map(|_| metal.scatter(&r, &rec))
+                .map(|out| dot(out.scattered.dir.unit_vector(), mirror).min(1.0))
+                .collect();
+            cosines.iter().map(|c| c.acos()).sum::<Float>() / cosines.len() as Float
+        };
+
+        let mirror_like = GgxMetal::new(Color::new(0.9, 0.9, 0.9), 0.0);
+        let out = mirror_like.scatter(&r, &rec).unwrap();
+        assert!((out.scattered.dir.unit_vector() - mirror).length() < 1e-6);
+        // at normal-ish angles Fresnel and the geometry terms barely change the albedo
+        assert!((out.attenuation - Color::new(0.9, 0.9, 0.9)).length() < 0.01);
+
+        let smooth = mean_spread(&GgxMetal::new(COLOR_WHITE, 0.2));
+        let rough = mean_spread(&GgxMetal::new(COLOR_WHITE, 0.8));
+        assert!(smooth < 0.2, "smooth spread {smooth}");
+        assert!(
+            rough > 3.0 * smooth,
+            "rough spread {rough}, smooth {smooth}"
+        );
+    }
+
+    #[test]
+    fn test_phong_shininess_tightens_highlight() {
+        crate::util::seed_rng(4);
+        let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+        let dir = Vec3::new(1.0, -1.0, 0.0);
+        let r = Ray::new(rec.p - dir, dir);
+        let mirror = reflect(dir.unit_vector(), rec.normal);
+
+        let mean_cos = |phong: &Phong| {
+            let cosines: Vec<Float> = (0..2000)
+                .filter_map(|_| phong.scatter(&r, &rec))
+                .map(|out| dot(out.scattered.dir.unit_vector(), mirror))
+                .collect();
+            cosines.iter().sum::<Float>() / cosines.len() as Float
+        };
+
+        let glossy = Phong::new(COLOR_BLACK, COLOR_WHITE, 1000.0);
+        let dull = Phong::new(COLOR_BLACK, COLOR_WHITE, 5.0);
+        assert!(mean_cos(&glossy) > 0.99);
+        assert!(mean_cos(&dull) < 0.9);
+
+        // with no specular color, everything bounces diffusely off the surface
+        let matte = Phong::new(COLOR_WHITE, COLOR_BLACK, 1000.0);
+        let out = matte.scatter(&r, &rec).unwrap();
+        assert_eq!(out.attenuation, COLOR_WHITE);
+        assert!(dot(out.scattered.dir, rec.normal) > 0.0);
+    }
+
+    #[test]
+    fn test_anisotropic_asymmetry_biases_scattering() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -2.0));
+        // mean cos and cos^2 of the angle between incoming and scattered directions
+        let moments = |mat: &dyn Material| {
+            crate::util::seed_rng(8);
+            let rec = test_record(Arc::new(Isotropic::new(COLOR_WHITE)));
+            let n = 20000;
+            let (sum, sum_sq) = (0..n).fold((0.0, 0.0), |(sum, sum_sq), _| {
+                let out = mat.scatter(&r, &rec).unwrap();
+                let cos = dot(out.scattered.dir.unit_vector(), r.dir.unit_vector());
+                (sum + cos, sum_sq + cos * cos)
+            });
+            (sum / n as Float, sum_sq / n as Float)
+        };
+
+        // like isotropic scattering, uniform over the sphere: E[cos] = 0, E[cos^2] = 1/3
+        for mat in [
+            &Anisotropic::new(COLOR_WHITE, 0.0) as &dyn Material,
+            &Isotropic::new(COLOR_WHITE),
+        ] {
+            let (mean, mean_sq) = moments(mat);
+            assert!(mean.abs() < 0.02, "{mean}");
+            assert!((mean_sq - 1.0 / 3.0).abs() < 0.02, "{mean_sq}");
+        }
+
+        // Henyey-Greenstein's mean cosine is g itself
+        let (forward, _) = moments(&Anisotropic::new(COLOR_WHITE, 0.7));
+        assert!((forward - 0.7).abs() < 0.02, "{forward}");
+        let (backward, _) = moments(&Anisotropic::new(COLOR_WHITE, -0.5));
+        assert!((backward + 0.5).abs() < 0.02, "{backward}");
+    }
+
+    #[test]
+    fn test_bumped_normal_map_tilts_scattering() {
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let lambertian: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let mean_direction = |mat: &dyn Material| {
+            crate::util::seed_rng(4);
+            let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+            (0..2000).fold(Vec3::new(0.0, 0.0, 0.0), |sum, _| {
+                sum + mat.scatter(&r, &rec).unwrap().scattered.dir.unit_vector()
+            }) / 2000.0
+        };
+
+        // a flat map scatters exactly like the bare material
+        let flat = Bumped::new(
+            Arc::clone(&lambertian),
+            Arc::new(SolidColor::new(Color::new(0.5, 0.5, 1.0))),
+        );
+        assert_eq!(mean_direction(&flat), mean_direction(lambertian.as_ref()));
+
+        // a normal map tilted toward the tangent (+x) leans the lobe the same way
+        let tilted = 0.5 * (Vec3::new(1.0, 0.0, 1.0).unit_vector() + COLOR_WHITE);
+        let tilted = Bumped::new(Arc::clone(&lambertian), Arc::new(SolidColor::new(tilted)));
+        let mean = mean_direction(&tilted);
+        assert!(mean.x() > 0.4, "{mean:?}");
+        assert!(mean.z().abs() < 0.1, "{mean:?}");
+
+        // the perturbed hit is still the same object
+        let rec = HitRecord {
+            obj_id: 7,
+            ..test_record(Arc::clone(&lambertian))
+        };
+        assert_eq!(tilted.perturbed(&rec).obj_id(), 7);
+    }
+
+    #[test]
+    fn test_coated_diffuse_gets_direct_light() {
+        let lambertian: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let coated: Arc<dyn Material> = Arc::new(Coated::new(Arc::clone(&lambertian), 1.5));
+        let light = PointLight::new(Point3::new(0.0, 1.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let r = Ray::new(Point3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0));
+        let direct = |mat| light.contribution(&HitList::new(), &r, &test_record(mat));
+
+        let bare = direct(lambertian);
+        let through_coat = direct(coated);
+        assert!(through_coat.x() > 0.0);
+        // 45° in, so a few percent of the light glances off the coat instead
+        let transmitted = 1.0 - reflectance(Float::sqrt(0.5), 1.0 / 1.5);
+        assert!((through_coat - transmitted * bare).length() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_coat_reflects_more_at_grazing_angles() {
+        let coated = Coated::new(Arc::new(Lambertian::new(COLOR_WHITE)), 1.5);
+        let rec = test_record(Arc::new(Lambertian::new(COLOR_WHITE)));
+        // share of rays bouncing off the coat, which leave in the mirror direction
+        let coat_fraction = |dir: Vec3| {
+            crate::util::seed_rng(2);
+            let r = Ray::new(Point3::new(0.0, 0.0, 0.0) - dir, dir);
+            let mirror = reflect(dir.unit_vector(), rec.normal);
+            let n = 2000;
+            let reflected = (0..n)
+                .filter(|_| {
+                    let out = coated.scatter(&r, &rec).unwrap();
+                    if out.is_specular {
+                        assert!((out.scattered.dir.unit_vector() - mirror).length() < 1e-6);
+                    }
+                    out.is_specular
+                })
+                .count();
+            reflected as Float / n as Float
+        };
+
+        let head_on = coat_fraction(Vec3::new(0.0, -1.0, 0.0));
+        let grazing = coat_fraction(Vec3::new(1.0, -0.01, 0.0));
+        assert!(
+            head_on < 0.1,
+            "{head_on} of head-on rays reflected off the coat"
+        );
+        assert!(
+            grazing > 0.8,
+            "{grazing} of grazing rays reflected off the coat"
+        );
+    }
+
+    #[test]
+    fn test_coat_bounces_are_flagged_specular() {
+        let base: Arc<dyn Material> = Arc::new(Lambertian::new(COLOR_WHITE));
+        let coated: Arc<dyn Material> = Arc::new(Coated::new(Arc::clone(&base), 1.5));
+        // a wrapper around the coat passes its flag through
+        let flat_map = Arc::new(SolidColor::new(Color::new(0.5, 0.5, 1.0)));
+        let bumped = Bumped::new(Arc::clone(&coated), flat_map);
+        let rec = test_record(Arc::clone(&coated));
+        let r = Ray::new(Point3::new(-1.0, 0.01, 0.0), Vec3::new(1.0, -0.01, 0.0));
+
+        crate::util::seed_rng(5);
+        let (mut coat, mut diffuse) = (0, 0);
+        for _ in 0..200 {
+            let out = bumped.scatter(&r, &rec).unwrap();
+            if out.is_specular {
+                coat += 1;
+            } else {
+                diffuse += 1;
+                assert!(coated.scattering_pdf(&r, &rec, &out.scattered).unwrap() > 0.0);
+            }
+        }
+        assert!(
+            coat > 0 && diffuse > 0,
+            "{coat} coat, {diffuse} diffuse bounces"
         );
+
+        // the mirror direction is just another direction to the base's lobe
+        let mirror = Ray::new(rec.p, reflect(r.dir.unit_vector(), rec.normal));
+        let through_coat = 1.0 - reflectance(0.01 / Float::sqrt(1.0001), 1.0 / 1.5);
+        let base_pdf = base.scattering_pdf(&r, &rec, &mirror).unwrap();
+        let pdf = coated.scattering_pdf(&r, &rec, &mirror).unwrap();
+        assert!((pdf - through_coat * base_pdf).abs() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_reflectance_grows_at_grazing_angles() {
+        let refraction_ratio = 1.0 / 1.5;
+        assert!(reflectance(1.0, refraction_ratio) < 0.05);
+        assert!(reflectance(0.05, refraction_ratio) > 0.5);
+    }
+
+    #[test]
+    fn test_colored_dialectric_absorbs_with_distance() {
+        let glass = Dialectric::new(1.5).with_absorption(Color::new(0.2, 1.0, 0.2), 1.0);
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let exit_after = |t: Float| {
+            let rec = HitRecord {
+                t,
+                front_face: false,
+                ..test_record(Arc::new(Dialectric::new(1.5)))
+            };
+            glass.scatter(&r, &rec).unwrap().attenuation
+        };
+
+        let thin = exit_after(0.1);
+        let thick = exit_after(2.0);
+        assert!(thick.x() < thin.x() && thin.x() < 1.0);
+        // green passes through untouched
+        assert_eq!(thin.y(), 1.0);
+        assert_eq!(thick.y(), 1.0);
+        assert!((thick.x() - Float::exp(-0.8 * 2.0)).abs() < 1e-12);
+
+        // entering the glass isn't tinted yet
+        let entry = test_record(Arc::new(Dialectric::new(1.5)));
+        assert_eq!(glass.scatter(&r, &entry).unwrap().attenuation, COLOR_WHITE);
+    }
+
+    #[test]
+    fn test_dialectric_reflects_more_at_grazing_incidence() {
+        let glass = Dialectric::new(1.5);
+        let rec = test_record(Arc::new(Dialectric::new(1.5)));
+        let reflected_count = |dir: Vec3| {
+            (0..2000)
+                .filter(|_| {
+                    let r = Ray::new(rec.p - dir, dir);
+                    let out = glass.scatter(&r, &rec).unwrap();
+                    out.scattered.dir.y() > 0.0
+                })
+                .count()
+        };
+
+        let normal = reflected_count(Vec3::new(0.0, -1.0, 0.0));
+        let grazing = reflected_count(Vec3::new(1.0, -0.02, 0.0));
+        assert!(normal < 200, "normal incidence reflected {normal} times");
+        assert!(grazing > 5 * normal, "grazing reflected {grazing} times");
     }
 }
@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::material::Material;
+use crate::triangle::Triangle;
+use crate::vec3::Point3;
+use crate::Float;
+use crate::HitList;
+
+// Loads the triangles of a Wavefront OBJ file, all sharing the material `mat`.
+pub fn load_obj(path: &Path, mat: Arc<dyn Material>) -> io::Result<HitList> {
+    let triangles = parse_obj(BufReader::new(File::open(path)?), mat)?;
+
+    let mut list = HitList::new();
+    for triangle in triangles {
+        list.add(Box::new(triangle));
+    }
+    Ok(list)
+}
+
+// Parses vertex (`v`) and face (`f`) lines, splitting polygons into a fan of triangles.
+// Normals, texture coordinates and everything else are ignored for now.
+pub fn parse_obj<R: BufRead>(reader: R, mat: Arc<dyn Material>) -> io::Result<Vec<Triangle>> {
+    let mut vertices: Vec<Point3> = Vec::new();
+    let mut triangles = Vec::new();
+
+    for (line_no, line) in reader.lines().enumerate() {
+        let line = line?;
+        let invalid = |msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {msg}: {line}", line_no + 1),
+            )
+        };
+
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let coords: Vec<Float> = tokens
+                    .take(3)
+                    .map(|t| t.parse::<Float>())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| invalid("bad vertex coordinate"))?;
+                if coords.len() != 3 {
+                    return Err(invalid("vertex needs 3 coordinates"));
+                }
+                vertices.push(Point3::new(coords[0], coords[1], coords[2]));
+            }
+            Some("f") => {
+                // Each entry looks like `v`, `v/vt`, `v//vn` or `v/vt/vn`; only `v` matters here.
+                let face: Vec<Point3> = tokens
+                    .map(|t| {
+                        let index: i64 = t
+                            .split('/')
+                            .next()
+                            .and_then(|v| v.parse().ok())
+                            .ok_or_else(|| invalid("bad face index"))?;
+                        // OBJ indices are 1-based; negative ones count back from the latest vertex.
+                        let resolved = if index < 0 {
+                            vertices.len() as i64 + index
+                        } else {
+                            index - 1
+                        };
+                        vertices
+                            .get(resolved as usize)
+                            .copied()
+                            .ok_or_else(|| invalid("face index out of range"))
+                    })
+                    .collect::<Result<_, _>>()?;
+                if face.len() < 3 {
+                    return Err(invalid("face needs at least 3 vertices"));
+                }
+                for i in 1..face.len() - 1 {
+                    triangles.push(Triangle::new(
+                        face[0],
+                        face[i],
+                        face[i + 1],
+                        Arc::clone(&mat),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(triangles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    const QUAD: &str = "# a unit quad
+v 0.0 0.0 0.0
+v 1.0 0.0 0.0
+v 1.0 1.0 0.0
+v 0.0 1.0 0.0
+vn 0.0 0.0 1.0
+vt 0.0 0.0
+f 1/1/1 2/1/1 3//1 -1
+";
+
+    #[test]
+    fn test_parse_quad() {
+        let mat = Arc::new(Lambertian::new(COLOR_WHITE));
+        let triangles = parse_obj(QUAD.as_bytes(), mat).unwrap();
+
+        assert_eq!(triangles.len(), 2);
+        let corners = |t: &Triangle| [t.v0, t.v1, t.v2];
+        assert_eq!(
+            corners(&triangles[0]),
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+            ]
+        );
+        assert_eq!(
+            corners(&triangles[1]),
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 1.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_index() {
+        let mat = Arc::new(Lambertian::new(COLOR_WHITE));
+        let err = parse_obj("v 0 0 0\nf 1 2 3\n".as_bytes(), mat)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use crate::aabb::{surrounding_box, Aabb};
+use crate::material::Material;
+use crate::vec3::{Point3, Vec3};
+use crate::Float;
+use crate::{hit_sphere, HitRecord, Hittable, Ray};
+
+// A sphere moving linearly from center0 (at time0) to center1 (at time1).
+pub struct MovingSphere {
+    pub center0: Point3,
+    pub center1: Point3,
+    pub time0: Float,
+    pub time1: Float,
+    pub radius: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl MovingSphere {
+    pub fn new(
+        center0: Point3,
+        center1: Point3,
+        time0: Float,
+        time1: Float,
+        radius: Float,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        MovingSphere {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            mat_ptr,
+        }
+    }
+
+    pub fn center(&self, time: Float) -> Point3 {
+        // an empty interval has nowhere to move to
+        if self.time1 == self.time0 {
+            return self.center0;
+        }
+        self.center0
+            + ((time - self.time0) / (self.time1 - self.time0)) * (self.center1 - self.center0)
+    }
+}
+
+impl Hittable for MovingSphere {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        hit_sphere(
+            self.center(r.time()),
+            self.radius,
+            &self.mat_ptr,
+            r,
+            t_min,
+            t_max,
+        )
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        let radius = self.radius.abs();
+        let r = Vec3::new(radius, radius, radius);
+        let box0 = Aabb::new(self.center(time0) - r, self.center(time0) + r);
+        let box1 = Aabb::new(self.center(time1) - r, self.center(time1) + r);
+        Some(surrounding_box(&box0, &box1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    fn moving_sphere() -> MovingSphere {
+        MovingSphere::new(
+            Point3::new(0.0, 0.0, -5.0),
+            Point3::new(10.0, 0.0, -5.0),
+            0.0,
+            1.0,
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_bounding_box_spans_both_endpoints() {
+        let bbox = moving_sphere().bounding_box(0.0, 1.0).unwrap();
+        assert_eq!(
+            bbox,
+            Aabb::new(Point3::new(-1.0, -1.0, -6.0), Point3::new(11.0, 1.0, -4.0))
+        );
+    }
+
+    #[test]
+    fn test_hit_depends_on_ray_time() {
+        let sphere = moving_sphere();
+        let at_start = Ray::with_time(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0), 0.0);
+        let at_end = Ray::with_time(Point3::new(10.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0), 1.0);
+
+        let rec = sphere.hit(&at_start, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -4.0));
+        let rec = sphere.hit(&at_end, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.p, Point3::new(10.0, 0.0, -4.0));
+
+        // by time 1 the sphere has moved out of the first ray's path
+        let late = Ray::with_time(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0), 1.0);
+        assert!(sphere.hit(&late, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_empty_time_interval_stays_at_center0() {
+        let sphere = MovingSphere::new(
+            Point3::new(0.0, 0.0, -5.0),
+            Point3::new(10.0, 0.0, -5.0),
+            0.5,
+            0.5,
+            1.0,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        assert_eq!(sphere.center(0.5), Point3::new(0.0, 0.0, -5.0));
+        let r = Ray::with_time(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0), 0.5);
+        let rec = sphere.hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -4.0));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::vec3::Vec3;
+use crate::Float;
+
+// Orthonormal basis
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    // Builds a basis whose w axis points along `n`.
+    pub fn build_from_w(n: Vec3) -> Onb {
+        let w = n.unit_vector();
+        let a = if w.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let v = w.cross(a).unit_vector();
+        let u = w.cross(v);
+        Onb { u, v, w }
+    }
+
+    // Converts coordinates in this basis to world coordinates.
+    pub fn local(&self, a: Float, b: Float, c: Float) -> Vec3 {
+        a * self.u + b * self.v + c * self.w
+    }
+
+    pub fn local_vec(&self, a: Vec3) -> Vec3 {
+        self.local(a.x(), a.y(), a.z())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tolerance;
+    use crate::vec3::dot;
+
+    #[test]
+    fn test_basis_is_orthonormal() {
+        for n in [
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, -2.0, 3.0),
+        ] {
+            let onb = Onb::build_from_w(n);
+            assert!((onb.w - n.unit_vector()).length() < tolerance(1e-12));
+            for (a, b) in [(onb.u, onb.v), (onb.v, onb.w), (onb.w, onb.u)] {
+                assert!(dot(a, b).abs() < tolerance(1e-12));
+                assert!((a.length() - 1.0).abs() < tolerance(1e-12));
+            }
+            assert!((onb.local(0.0, 0.0, 2.0) - 2.0 * onb.w).length() < tolerance(1e-12));
+        }
+    }
+}
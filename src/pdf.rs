@@ -0,0 +1,152 @@
+use crate::consts::PI;
+
+use crate::light::PdfHittable;
+use crate::onb::Onb;
+use crate::util::random_double;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+
+// A distribution of directions that can be both sampled and evaluated.
+pub trait Pdf {
+    // Probability density (over solid angle) of `generate` choosing `direction`.
+    fn value(&self, direction: Vec3) -> Float;
+
+    fn generate(&self) -> Vec3;
+}
+
+// Directions around `w`, proportional to the cosine of their angle to it.
+pub struct CosinePdf {
+    onb: Onb,
+}
+
+impl CosinePdf {
+    pub fn new(w: Vec3) -> Self {
+        CosinePdf {
+            onb: Onb::build_from_w(w),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: Vec3) -> Float {
+        let cosine = dot(direction.unit_vector(), self.onb.w);
+        Float::max(cosine, 0.0) / PI
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.onb.local_vec(Vec3::random_cosine_direction())
+    }
+}
+
+// Directions from `origin` toward an object.
+pub struct HittablePdf<'a> {
+    origin: Point3,
+    object: &'a dyn PdfHittable,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(origin: Point3, object: &'a dyn PdfHittable) -> Self {
+        HittablePdf { origin, object }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: Vec3) -> Float {
+        self.object.pdf_value(self.origin, direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.object.random_to(self.origin)
+    }
+}
+
+// Picks one of several distributions at random, by weight.
+pub struct MixturePdf<'a> {
+    // (weight, distribution), with weights summing to 1
+    pdfs: Vec<(Float, Box<dyn Pdf + 'a>)>,
+}
+
+impl<'a> MixturePdf<'a> {
+    // Normalizes the weights so that they sum to 1.
+    pub fn new(pdfs: Vec<(Float, Box<dyn Pdf + 'a>)>) -> Self {
+        let total: Float = pdfs.iter().map(|(weight, _)| weight).sum();
+        MixturePdf {
+            pdfs: pdfs
+                .into_iter()
+                .map(|(weight, pdf)| (weight / total, pdf))
+                .collect(),
+        }
+    }
+
+    // Every distribution equally likely.
+    pub fn uniform(pdfs: Vec<Box<dyn Pdf + 'a>>) -> Self {
+        Self::new(pdfs.into_iter().map(|pdf| (1.0, pdf)).collect())
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: Vec3) -> Float {
+        self.pdfs
+            .iter()
+            .map(|(weight, pdf)| weight * pdf.value(direction))
+            .sum()
+    }
+
+    fn generate(&self) -> Vec3 {
+        let mut pick = random_double();
+        for (weight, pdf) in &self.pdfs {
+            if pick < *weight {
+                return pdf.generate();
+            }
+            pick -= weight;
+        }
+        // only reachable through rounding
+        self.pdfs.last().expect("empty MixturePdf").1.generate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::DiffuseLight;
+    use crate::util::seed_rng;
+    use crate::vec3::Color;
+    use crate::Sphere;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cosine_pdf() {
+        seed_rng(8);
+        let pdf = CosinePdf::new(Vec3::new(0.0, 0.0, 2.0));
+        assert!((pdf.value(Vec3::new(0.0, 0.0, 1.0)) - 1.0 / PI).abs() < 1e-12);
+        assert_eq!(pdf.value(Vec3::new(0.0, 0.0, -1.0)), 0.0);
+        for _ in 0..100 {
+            assert!(pdf.generate().z() >= 0.0);
+        }
+    }
+
+    #[test]
+    fn test_mixture_pdf() {
+        seed_rng(8);
+        let light = Sphere::new(
+            Point3::new(0.0, 0.0, 4.0),
+            1.0,
+            Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0))),
+        );
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        let mixture = MixturePdf::new(vec![
+            (1.0, Box::new(CosinePdf::new(Vec3::new(0.0, 0.0, 1.0)))),
+            (3.0, Box::new(HittablePdf::new(origin, &light))),
+        ]);
+
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let expected = 0.25 / PI + 0.75 * light.pdf_value(origin, up);
+        assert!((mixture.value(up) - expected).abs() < 1e-12);
+
+        // about three quarters of the samples head for the light
+        let toward_light = (0..4000)
+            .filter(|_| light.pdf_value(origin, mixture.generate()) > 0.0)
+            .count();
+        assert!((3000..3300).contains(&toward_light), "{toward_light}");
+    }
+}
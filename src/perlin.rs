@@ -0,0 +1,131 @@
+use crate::util::random_int;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+
+const POINT_COUNT: usize = 256;
+
+// Perlin gradient noise
+pub struct Perlin {
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new() -> Perlin {
+        let ranvec = (0..POINT_COUNT)
+            .map(|_| Vec3::new_random_bounded(-1.0, 1.0).unit_vector())
+            .collect();
+
+        Perlin {
+            ranvec,
+            perm_x: Perlin::generate_perm(),
+            perm_y: Perlin::generate_perm(),
+            perm_z: Perlin::generate_perm(),
+        }
+    }
+
+    // Returns smoothly varying noise in [-1, 1].
+    pub fn noise(&self, p: Point3) -> Float {
+        let u = p.x() - p.x().floor();
+        let v = p.y() - p.y().floor();
+        let w = p.z() - p.z().floor();
+
+        let i = p.x().floor() as i64;
+        let j = p.y().floor() as i64;
+        let k = p.z().floor() as i64;
+
+        let mut c = [[[Vec3::new(0.0, 0.0, 0.0); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    *corner = self.ranvec[self.perm_x[((i + di as i64) & 255) as usize]
+                        ^ self.perm_y[((j + dj as i64) & 255) as usize]
+                        ^ self.perm_z[((k + dk as i64) & 255) as usize]];
+                }
+            }
+        }
+
+        Perlin::perlin_interp(&c, u, v, w)
+    }
+
+    // Sums `depth` octaves of noise, each at double the frequency and half the weight.
+    pub fn turbulence(&self, p: Point3, depth: u32) -> Float {
+        let mut accum = 0.0;
+        let mut temp_p = p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum.abs()
+    }
+
+    fn generate_perm() -> Vec<usize> {
+        let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+        // Fisher-Yates shuffle
+        for i in (1..POINT_COUNT).rev() {
+            let target = random_int(0, i as i32) as usize;
+            p.swap(i, target);
+        }
+        p
+    }
+
+    fn perlin_interp(c: &[[[Vec3; 2]; 2]; 2], u: Float, v: Float, w: Float) -> Float {
+        // Hermite cubic smoothing
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+
+        let mut accum = 0.0;
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as Float, j as Float, k as Float);
+                    let weight_v = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * dot(*corner, weight_v);
+                }
+            }
+        }
+        accum
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_is_bounded_and_deterministic() {
+        let perlin = Perlin::new();
+        for x in -10..10 {
+            for y in -10..10 {
+                for z in -2..2 {
+                    let p = Point3::new(x as Float * 0.37, y as Float * 0.53, z as Float * 1.1);
+                    let n = perlin.noise(p);
+                    assert!((-1.0..=1.0).contains(&n), "noise({:?}) = {n}", p);
+                    assert_eq!(n, perlin.noise(p));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_noise_is_zero_on_lattice_points() {
+        let perlin = Perlin::new();
+        assert_eq!(perlin.noise(Point3::new(3.0, -2.0, 7.0)), 0.0);
+    }
+}
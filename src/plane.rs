@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// An infinite plane through `point`, facing along `normal`.
+pub struct Plane {
+    pub point: Point3,
+    pub normal: Vec3,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Plane {
+    pub fn new(point: Point3, normal: Vec3, mat_ptr: Arc<dyn Material>) -> Self {
+        Plane {
+            point,
+            normal: normal.unit_vector(),
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let denom = dot(r.dir, self.normal);
+        // the ray is parallel to the plane
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = dot(self.point - r.orig, self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        // (u, v) are unbounded coordinates along two tangent directions of the plane
+        let a = if self.normal.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let tangent = self.normal.cross(a).unit_vector();
+        let bitangent = self.normal.cross(tangent);
+
+        let p = r.at(t);
+        let hr = HitRecord {
+            p,
+            normal: self.normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: dot(p - self.point, tangent),
+            v: dot(p - self.point, bitangent),
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, self.normal))
+    }
+
+    // A plane is unbounded.
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    fn ground() -> Plane {
+        Plane::new(
+            Point3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_ray_hits_plane() {
+        let r = Ray::new(Point3::new(3.0, 1.0, 4.0), Vec3::new(0.0, -1.0, 0.0));
+        let rec = ground().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 2.0);
+        assert_eq!(rec.p, Point3::new(3.0, -1.0, 4.0));
+        assert!(rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_parallel_ray_misses() {
+        let r = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 1.0));
+        assert!(ground().hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_hit_from_below_flips_normal() {
+        let r = Ray::new(Point3::new(0.0, -3.0, 0.0), Vec3::new(1.0, 1.0, 0.0));
+        let rec = ground().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert!(ground().bounding_box(0.0, 1.0).is_none());
+    }
+}
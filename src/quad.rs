@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// A parallelogram with corner `q` and edges `u` and `v`.
+pub struct Quad {
+    pub q: Point3,
+    pub u: Vec3,
+    pub v: Vec3,
+    pub mat_ptr: Arc<dyn Material>,
+    normal: Vec3,
+    // n / dot(n, n), for solving the planar coordinates of a hit
+    w: Vec3,
+}
+
+impl Quad {
+    pub fn new(q: Point3, u: Vec3, v: Vec3, mat_ptr: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        Quad {
+            q,
+            u,
+            v,
+            mat_ptr,
+            normal: n.unit_vector(),
+            w: n / dot(n, n),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let denom = dot(self.normal, r.dir);
+        // the ray is parallel to the quad
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = dot(self.q - r.orig, self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        // p = q + alpha * u + beta * v
+        let p = r.at(t);
+        let planar = p - self.q;
+        let alpha = dot(self.w, planar.cross(self.v));
+        let beta = dot(self.w, self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let hr = HitRecord {
+            p,
+            normal: self.normal,
+            tangent: self.u.unit_vector(),
+            t,
+            u: alpha,
+            v: beta,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, self.normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        let corners = [self.q + self.u, self.q + self.v, self.q + self.u + self.v];
+        let (min, max) = corners
+            .iter()
+            .fold((self.q, self.q), |(min, max), &c| (min.min(c), max.max(c)));
+        // Pad so that axis-aligned quads don't get a zero-width box.
+        let pad = Vec3::new(0.0001, 0.0001, 0.0001);
+        Some(Aabb::new(min - pad, max + pad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::COLOR_WHITE;
+
+    // a 2x1 rectangle at z = -1, from (0, 0) to (2, 1)
+    fn quad() -> Quad {
+        Quad::new(
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    fn toward(x: Float, y: Float) -> Ray {
+        Ray::new(Point3::new(x, y, 0.0), Vec3::new(0.0, 0.0, -1.0))
+    }
+
+    #[test]
+    fn test_interior_hit() {
+        let rec = quad()
+            .hit(&toward(0.5, 0.25), 0.001, Float::INFINITY)
+            .unwrap();
+        assert_eq!(rec.t, 1.0);
+        assert_eq!((rec.u, rec.v), (0.25, 0.25));
+        assert!(rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_corner_hit() {
+        let rec = quad()
+            .hit(&toward(2.0, 1.0), 0.001, Float::INFINITY)
+            .unwrap();
+        assert_eq!((rec.u, rec.v), (1.0, 1.0));
+        let rec = quad()
+            .hit(&toward(0.0, 0.0), 0.001, Float::INFINITY)
+            .unwrap();
+        assert_eq!((rec.u, rec.v), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_just_outside_edge_misses() {
+        assert!(quad()
+            .hit(&toward(2.001, 0.5), 0.001, Float::INFINITY)
+            .is_none());
+        assert!(quad()
+            .hit(&toward(1.0, -0.001), 0.001, Float::INFINITY)
+            .is_none());
+
+        let parallel = Ray::new(Point3::new(0.0, 0.0, -1.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(quad().hit(&parallel, 0.001, Float::INFINITY).is_none());
+
+        let bbox = quad().bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(0.0, 0.0, -1.0)).length() < 1e-3);
+        assert!((bbox.maximum - Point3::new(2.0, 1.0, -1.0)).length() < 1e-3);
+    }
+}
@@ -0,0 +1,363 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitList, HitRecord, Hittable, Ray};
+
+// Axis-aligned rectangles. Each lies in the plane where its third axis equals `k`.
+
+// Rectangles have zero thickness, so pad their bounding boxes along the fixed axis.
+const PAD: Float = 0.0001;
+
+pub struct XyRect {
+    pub x0: Float,
+    pub x1: Float,
+    pub y0: Float,
+    pub y1: Float,
+    pub k: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl XyRect {
+    pub fn new(
+        x0: Float,
+        x1: Float,
+        y0: Float,
+        y1: Float,
+        k: Float,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        XyRect {
+            x0,
+            x1,
+            y0,
+            y1,
+            k,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for XyRect {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let t = (self.k - r.orig.z()) / r.dir.z();
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+        let x = r.orig.x() + t * r.dir.x();
+        let y = r.orig.y() + t * r.dir.y();
+        if x < self.x0 || x > self.x1 || y < self.y0 || y > self.y1 {
+            return None;
+        }
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: Vec3::new(0.0, 0.0, 1.0),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: (x - self.x0) / (self.x1 - self.x0),
+            v: (y - self.y0) / (self.y1 - self.y0),
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, Vec3::new(0.0, 0.0, 1.0)))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        Some(Aabb::new(
+            Point3::new(self.x0, self.y0, self.k - PAD),
+            Point3::new(self.x1, self.y1, self.k + PAD),
+        ))
+    }
+}
+
+pub struct XzRect {
+    pub x0: Float,
+    pub x1: Float,
+    pub z0: Float,
+    pub z1: Float,
+    pub k: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl XzRect {
+    pub fn new(
+        x0: Float,
+        x1: Float,
+        z0: Float,
+        z1: Float,
+        k: Float,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        XzRect {
+            x0,
+            x1,
+            z0,
+            z1,
+            k,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for XzRect {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let t = (self.k - r.orig.y()) / r.dir.y();
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+        let x = r.orig.x() + t * r.dir.x();
+        let z = r.orig.z() + t * r.dir.z();
+        if x < self.x0 || x > self.x1 || z < self.z0 || z > self.z1 {
+            return None;
+        }
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: (x - self.x0) / (self.x1 - self.x0),
+            v: (z - self.z0) / (self.z1 - self.z0),
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, Vec3::new(0.0, 1.0, 0.0)))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        Some(Aabb::new(
+            Point3::new(self.x0, self.k - PAD, self.z0),
+            Point3::new(self.x1, self.k + PAD, self.z1),
+        ))
+    }
+}
+
+pub struct YzRect {
+    pub y0: Float,
+    pub y1: Float,
+    pub z0: Float,
+    pub z1: Float,
+    pub k: Float,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl YzRect {
+    pub fn new(
+        y0: Float,
+        y1: Float,
+        z0: Float,
+        z1: Float,
+        k: Float,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        YzRect {
+            y0,
+            y1,
+            z0,
+            z1,
+            k,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for YzRect {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let t = (self.k - r.orig.x()) / r.dir.x();
+        if !(t_min..=t_max).contains(&t) {
+            return None;
+        }
+        let y = r.orig.y() + t * r.dir.y();
+        let z = r.orig.z() + t * r.dir.z();
+        if y < self.y0 || y > self.y1 || z < self.z0 || z > self.z1 {
+            return None;
+        }
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: (y - self.y0) / (self.y1 - self.y0),
+            v: (z - self.z0) / (self.z1 - self.z0),
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, Vec3::new(1.0, 0.0, 0.0)))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        Some(Aabb::new(
+            Point3::new(self.k - PAD, self.y0, self.z0),
+            Point3::new(self.k + PAD, self.y1, self.z1),
+        ))
+    }
+}
+
+// An axis-aligned box, made of six rectangles.
+pub struct BoxPrim {
+    pub box_min: Point3,
+    pub box_max: Point3,
+    sides: HitList,
+}
+
+impl BoxPrim {
+    pub fn new(p0: Point3, p1: Point3, mat_ptr: Arc<dyn Material>) -> Self {
+        let mut sides = HitList::new();
+
+        let (x0, y0, z0) = (p0.x(), p0.y(), p0.z());
+        let (x1, y1, z1) = (p1.x(), p1.y(), p1.z());
+        sides.add(Box::new(XyRect::new(
+            x0,
+            x1,
+            y0,
+            y1,
+            z1,
+            Arc::clone(&mat_ptr),
+        )));
+        sides.add(Box::new(XyRect::new(
+            x0,
+            x1,
+            y0,
+            y1,
+            z0,
+            Arc::clone(&mat_ptr),
+        )));
+        sides.add(Box::new(XzRect::new(
+            x0,
+            x1,
+            z0,
+            z1,
+            y1,
+            Arc::clone(&mat_ptr),
+        )));
+        sides.add(Box::new(XzRect::new(
+            x0,
+            x1,
+            z0,
+            z1,
+            y0,
+            Arc::clone(&mat_ptr),
+        )));
+        sides.add(Box::new(YzRect::new(
+            y0,
+            y1,
+            z0,
+            z1,
+            x1,
+            Arc::clone(&mat_ptr),
+        )));
+        sides.add(Box::new(YzRect::new(y0, y1, z0, z1, x0, mat_ptr)));
+
+        BoxPrim {
+            box_min: p0,
+            box_max: p1,
+            sides,
+        }
+    }
+}
+
+impl Hittable for BoxPrim {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let rec = self.sides.hit(r, t_min, t_max)?;
+        // The sides' normals all point along +axis; make them point out of the box
+        // so front_face reports whether the ray arrives from outside.
+        let center = (self.box_min + self.box_max) / 2.0;
+        let outward_normal = if dot(rec.normal, rec.p - center) > 0.0 {
+            rec.normal
+        } else {
+            -rec.normal
+        };
+        Some(rec.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, time0: Float, time1: Float) -> Option<Aabb> {
+        self.sides.bounding_box(time0, time1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::mat;
+
+    #[test]
+    fn test_xy_rect() {
+        let rect = XyRect::new(-1.0, 1.0, -1.0, 1.0, -2.0, mat());
+        let down_axis = Ray::new(Point3::new(0.5, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = rect.hit(&down_axis, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 2.0);
+        assert_eq!((rec.u, rec.v), (0.75, 0.5));
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+
+        let outside = Ray::new(Point3::new(1.5, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(rect.hit(&outside, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_xz_rect() {
+        let rect = XzRect::new(0.0, 2.0, 0.0, 2.0, 3.0, mat());
+        let up_axis = Ray::new(Point3::new(1.0, 0.0, 1.0), Vec3::new(0.0, 1.0, 0.0));
+        let rec = rect.hit(&up_axis, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 3.0);
+        // hit from below, so the normal faces down toward the ray
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, -1.0, 0.0));
+
+        let outside = Ray::new(Point3::new(1.0, 0.0, -0.5), Vec3::new(0.0, 1.0, 0.0));
+        assert!(rect.hit(&outside, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_yz_rect() {
+        let rect = YzRect::new(0.0, 1.0, 0.0, 1.0, 5.0, mat());
+        let along_axis = Ray::new(Point3::new(0.0, 0.5, 0.5), Vec3::new(2.0, 0.0, 0.0));
+        let rec = rect.hit(&along_axis, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 2.5);
+        assert_eq!(rec.p, Point3::new(5.0, 0.5, 0.5));
+
+        let outside = Ray::new(Point3::new(0.0, 1.5, 0.5), Vec3::new(1.0, 0.0, 0.0));
+        assert!(rect.hit(&outside, 0.001, Float::INFINITY).is_none());
+        // the rect is behind t_max
+        assert!(rect.hit(&along_axis, 0.001, 2.0).is_none());
+    }
+
+    #[test]
+    fn test_rect_bounding_box_is_padded() {
+        let rect = XyRect::new(-1.0, 1.0, -1.0, 1.0, 0.0, mat());
+        let bbox = rect.bounding_box(0.0, 1.0).unwrap();
+        assert!(bbox.minimum.z() < 0.0 && bbox.maximum.z() > 0.0);
+    }
+
+    #[test]
+    fn test_box_hits_near_face() {
+        let cube = BoxPrim::new(
+            Point3::new(-0.5, -0.5, -0.5),
+            Point3::new(0.5, 0.5, 0.5),
+            mat(),
+        );
+
+        let inside = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = cube.hit(&inside, 0.001, Float::INFINITY).unwrap();
+        assert!(!rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+
+        for dir in [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ] {
+            let r = Ray::new(Point3::new(0.0, 0.0, 0.0) - 3.0 * dir, dir);
+            let rec = cube.hit(&r, 0.001, Float::INFINITY).unwrap();
+            assert_eq!(rec.t, 2.5);
+            assert!(rec.front_face);
+            assert_eq!(rec.normal, -dir);
+        }
+    }
+}
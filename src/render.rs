@@ -0,0 +1,1595 @@
+use std::io::{self, Write};
+#[cfg(feature = "png")]
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "png")]
+use image::{
+    error::{ParameterError, ParameterErrorKind},
+    ImageError, RgbImage,
+};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::background::{Background, GradientSky};
+use crate::light::{DirectionalLight, LightStrategy, PdfHittable, PointLight, SpotLight};
+use crate::tonemap::ToneMap;
+#[cfg(not(feature = "rayon"))]
+use crate::util::serial::*;
+use crate::util::{mix_seed, random_double, seed_rng};
+use crate::vec3::{Color, Vec3, COLOR_BLACK, COLOR_WHITE};
+use crate::Float;
+use crate::{Camera, Hittable, PathContext, Ray};
+
+// How sample positions are chosen within a pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Sampler {
+    // independent uniform offsets
+    #[default]
+    Uniform,
+    // one jittered sample per cell of an s×s grid, where s = floor(sqrt(samples));
+    // any leftover samples are uniform
+    Stratified,
+}
+
+impl Sampler {
+    // Offsets within a pixel, each in [0, 1) x [0, 1).
+    pub fn pixel_offsets(self, samples: u32) -> Vec<(Float, Float)> {
+        let mut offsets = Vec::with_capacity(samples as usize);
+        if self == Sampler::Stratified {
+            let s = (samples as Float).sqrt() as u32;
+            for sy in 0..s {
+                for sx in 0..s {
+                    offsets.push((
+                        (sx as Float + random_double()) / s as Float,
+                        (sy as Float + random_double()) / s as Float,
+                    ));
+                }
+            }
+        }
+        while offsets.len() < samples as usize {
+            offsets.push((random_double(), random_double()));
+        }
+        offsets
+    }
+}
+
+// What each camera ray computes.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RenderMode {
+    // full path tracing through the materials
+    #[default]
+    Shaded,
+    // debug view: color each first hit by its shading normal, 0.5 * (normal + 1)
+    ShadeNormals,
+}
+
+// Auxiliary buffers for denoising, ordered like the color buffer.
+pub struct Aovs {
+    // accumulated color sums, as returned by `Renderer::render`
+    pub color: Vec<Color>,
+    // first-hit shading normal, or zero where the ray missed
+    pub normal: Vec<Vec3>,
+    // first-hit ray parameter `t`, or infinity where the ray missed
+    pub depth: Vec<Float>,
+    // first-hit material base color, or the background where the ray missed
+    pub albedo: Vec<Color>,
+}
+
+// One finished tile of a `Renderer::render_tiled` image.
+pub struct TileResult {
+    // top-left corner, in pixels from the top-left of the image
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    // accumulated color sums, ordered top-to-bottom then left-to-right within the tile
+    pub pixels: Vec<Color>,
+}
+
+// A `Renderer::render_preview` image.
+pub struct Preview {
+    // averaged colors (write them with `samples = 1`), ordered top-to-bottom then
+    // left-to-right
+    pub pixels: Vec<Color>,
+    // rays shot through each pixel
+    pub samples: Vec<u32>,
+}
+
+// Extra rays for each pixel on a silhouette in `Renderer::render_preview`.
+const PREVIEW_EDGE_SAMPLES: u32 = 4;
+
+// Diagnostics gathered while rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RenderStats {
+    // paths cut off by `max_depth` rather than by missing or being absorbed; these
+    // contribute black, like a shadow would
+    pub depth_exhausted: u64,
+    // path rays tested against the world, not counting shadow rays
+    pub rays_traced: u64,
+}
+
+impl std::ops::AddAssign for RenderStats {
+    fn add_assign(&mut self, other: RenderStats) {
+        self.depth_exhausted += other.depth_exhausted;
+        self.rays_traced += other.rays_traced;
+    }
+}
+
+// Owns the image settings and the per-pixel sampling loop.
+pub struct Renderer {
+    pub image_width: u32,
+    pub image_height: u32,
+    pub samples_per_pixel: u32,
+    pub max_depth: i32,
+    // color of rays that don't hit anything
+    pub background: Arc<dyn Background>,
+    // renders with the same seed are identical
+    pub seed: u64,
+    // applied to each averaged pixel, before gamma correction
+    pub tone_map: ToneMap,
+    pub sampler: Sampler,
+    pub mode: RenderMode,
+    // sampled directly at every diffuse hit
+    pub lights: Vec<DirectionalLight>,
+    // also sampled directly at every diffuse hit
+    pub point_lights: Vec<PointLight>,
+    pub spot_lights: Vec<SpotLight>,
+    // emitters in the world worth sending diffuse bounces toward
+    pub area_lights: Vec<Arc<dyn PdfHittable>>,
+    pub light_strategy: LightStrategy,
+    // caps each sample's luminance, trading a little bias for no fireflies
+    pub firefly_clamp: Option<Float>,
+    // scales the background seen by bounced rays (camera rays always see all of it), e.g.
+    // 0 to keep sky light out of enclosed scenes
+    pub indirect_background_scale: Float,
+}
+
+impl Renderer {
+    pub fn new(image_width: u32, image_height: u32) -> Renderer {
+        Renderer {
+            image_width,
+            image_height,
+            samples_per_pixel: 100,
+            max_depth: 50,
+            background: Arc::new(GradientSky::default()),
+            seed: 0,
+            tone_map: ToneMap::None,
+            sampler: Sampler::Uniform,
+            mode: RenderMode::Shaded,
+            lights: Vec::new(),
+            point_lights: Vec::new(),
+            spot_lights: Vec::new(),
+            area_lights: Vec::new(),
+            light_strategy: LightStrategy::Mis,
+            firefly_clamp: None,
+            indirect_background_scale: 1.0,
+        }
+    }
+
+    // Renders the image one scanline at a time.
+    // Returns accumulated color sums, ordered top-to-bottom then left-to-right.
+    pub fn render(&self, world: &impl Hittable, camera: &Camera) -> Vec<Color> {
+        (0..self.image_height)
+            .rev()
+            .flat_map(|j| self.render_row(world, camera, j))
+            .collect()
+    }
+
+    // Like `render`, but renders scanlines in parallel (with the `rayon` feature). The
+    // output is identical to `render`.
+    pub fn render_parallel(&self, world: &impl Hittable, camera: &Camera) -> Vec<Color> {
+        (0..self.image_height)
+            .into_par_iter()
+            .rev()
+            .flat_map_iter(|j| self.render_row(world, camera, j))
+            .collect()
+    }
+
+    // Like `render_parallel`, but also reports how the paths ended.
+    pub fn render_with_stats(
+        &self,
+        world: &impl Hittable,
+        camera: &Camera,
+    ) -> (Vec<Color>, RenderStats) {
+        let rows: Vec<(Vec<Color>, RenderStats)> = (0..self.image_height)
+            .into_par_iter()
+            .rev()
+            .map(|j| {
+                let mut stats = RenderStats::default();
+                let row = (0..self.image_width)
+                    .map(|i| self.render_pixel(world, camera, i, j, &mut stats))
+                    .collect();
+                (row, stats)
+            })
+            .collect();
+
+        let mut total = RenderStats::default();
+        let mut pixels = Vec::with_capacity((self.image_width * self.image_height) as usize);
+        for (row, stats) in rows {
+            pixels.extend(row);
+            total += stats;
+        }
+        (pixels, total)
+    }
+
+    // Renders the image in `tile_size` squares (smaller at the right and bottom edges),
+    // calling `on_tile` as each one completes. Reassembled, the tiles match `render`.
+    pub fn render_tiled(
+        &self,
+        world: &impl Hittable,
+        camera: &Camera,
+        tile_size: u32,
+        mut on_tile: impl FnMut(TileResult),
+    ) {
+        assert!(tile_size > 0, "tile_size must be positive");
+        for y in (0..self.image_height).step_by(tile_size as usize) {
+            for x in (0..self.image_width).step_by(tile_size as usize) {
+                let width = tile_size.min(self.image_width - x);
+                let height = tile_size.min(self.image_height - y);
+                let mut pixels = Vec::with_capacity((width * height) as usize);
+                for row in y..y + height {
+                    let j = self.image_height - 1 - row;
+                    for i in x..x + width {
+                        pixels.push(self.render_pixel(
+                            world,
+                            camera,
+                            i,
+                            j,
+                            &mut RenderStats::default(),
+                        ));
+                    }
+                }
+                on_tile(TileResult {
+                    x,
+                    y,
+                    width,
+                    height,
+                    pixels,
+                });
+            }
+        }
+    }
+
+    // Renders only the pixels with x0 <= x < x1 and y0 <= y < y1 (y counting rows from the
+    // top), in the same order as `render`. Rays are still aimed through the full image, so
+    // the crop matches those pixels of a full render.
+    pub fn render_region(
+        &self,
+        world: &impl Hittable,
+        camera: &Camera,
+        x0: u32,
+        y0: u32,
+        x1: u32,
+        y1: u32,
+    ) -> Vec<Color> {
+        assert!(
+            x0 <= x1 && x1 <= self.image_width && y0 <= y1 && y1 <= self.image_height,
+            "region must lie within the image"
+        );
+        (y0..y1)
+            .into_par_iter()
+            .flat_map_iter(|row| {
+                let j = self.image_height - 1 - row;
+                (x0..x1).map(move |i| {
+                    self.render_pixel(world, camera, i, j, &mut RenderStats::default())
+                })
+            })
+            .collect()
+    }
+
+    // Renders the color buffer along with first-hit normal, depth and albedo buffers.
+    pub fn render_aovs(&self, world: &impl Hittable, camera: &Camera) -> Aovs {
+        let color = self.render_parallel(world, camera);
+        let pixel_count = color.len();
+        let mut aovs = Aovs {
+            color,
+            normal: Vec::with_capacity(pixel_count),
+            depth: Vec::with_capacity(pixel_count),
+            albedo: Vec::with_capacity(pixel_count),
+        };
+
+        let width = self.image_width as Float;
+        let height = self.image_height as Float;
+        for j in (0..self.image_height).rev() {
+            seed_rng(mix_seed(self.seed, j as u64));
+            for i in 0..self.image_width {
+                // unjittered, with the same pixel mapping as the color samples
+                let ray = camera.get_ray(i as Float / (width - 1.0), j as Float / (height - 1.0));
+                match world.hit(&ray, 0.001, Float::INFINITY) {
+                    Some(rec) => {
+                        aovs.normal.push(rec.normal);
+                        aovs.depth.push(rec.t);
+                        aovs.albedo.push(rec.mat_ptr.albedo(rec.u, rec.v, rec.p));
+                    }
+                    None => {
+                        aovs.normal.push(Vec3::new(0.0, 0.0, 0.0));
+                        aovs.depth.push(Float::INFINITY);
+                        aovs.albedo.push(self.background.color(&ray));
+                    }
+                }
+            }
+        }
+        aovs
+    }
+
+    // A fast, low quality render: one ray through the center of each pixel, plus a few
+    // more where the first-hit object changes between neighboring pixels, which smooths
+    // silhouettes at little cost.
+    pub fn render_preview(&self, world: &impl Hittable, camera: &Camera) -> Preview {
+        let (width, height) = (self.image_width as usize, self.image_height as usize);
+        // rows top to bottom, like the output
+        let centers: Vec<(Color, Option<usize>)> = (0..self.image_height)
+            .into_par_iter()
+            .rev()
+            .flat_map_iter(|j| {
+                (0..self.image_width).map(move |i| {
+                    seed_rng(self.pixel_seed(i, j));
+                    let u = (i as Float + 0.5) / (self.image_width as Float - 1.0);
+                    let v = (j as Float + 0.5) / (self.image_height as Float - 1.0);
+                    let ray = camera.get_ray(u, v);
+                    let id = world
+                        .hit(&ray, 0.001, Float::INFINITY)
+                        .map(|rec| rec.obj_id());
+                    let color = self.trace(world, ray, &mut RenderStats::default());
+                    (
+                        self.clamp_firefly(color.sanitized() * camera.exposure()),
+                        id,
+                    )
+                })
+            })
+            .collect();
+
+        let on_edge = |x: usize, y: usize| {
+            let id = centers[y * width + x].1;
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            neighbors
+                .iter()
+                .any(|&(nx, ny)| nx < width && ny < height && centers[ny * width + nx].1 != id)
+        };
+
+        let (pixels, samples) = (0..height * width)
+            .into_par_iter()
+            .map(|index| {
+                let (x, y) = (index % width, index / width);
+                let mut color = centers[index].0;
+                let mut samples = 1;
+                if on_edge(x, y) {
+                    let (i, j) = (x as u32, (height - 1 - y) as u32);
+                    seed_rng(mix_seed(self.pixel_seed(i, j), 1));
+                    for offset in Sampler::Stratified.pixel_offsets(PREVIEW_EDGE_SAMPLES) {
+                        color +=
+                            self.sample(world, camera, i, j, offset, &mut RenderStats::default());
+                    }
+                    samples += PREVIEW_EDGE_SAMPLES;
+                }
+                (self.tone_map.apply(color / samples as Float), samples)
+            })
+            .unzip();
+        Preview { pixels, samples }
+    }
+
+    fn trace(&self, world: &impl Hittable, ray: Ray, stats: &mut RenderStats) -> Color {
+        match self.mode {
+            RenderMode::Shaded => {
+                let ctx = PathContext {
+                    world,
+                    lights: &self.lights,
+                    point_lights: &self.point_lights,
+                    spot_lights: &self.spot_lights,
+                    area_lights: &self.area_lights,
+                    background: self.background.as_ref(),
+                    strategy: self.light_strategy,
+                    primary_depth: self.max_depth,
+                    indirect_background_scale: self.indirect_background_scale,
+                };
+                ray.trace_path(&ctx, self.max_depth, None, stats)
+            }
+            RenderMode::ShadeNormals => match world.hit(&ray, 0.001, Float::INFINITY) {
+                Some(rec) => 0.5 * (rec.normal + COLOR_WHITE),
+                None => self.background.color(&ray),
+            },
+        }
+    }
+
+    fn render_row(&self, world: &impl Hittable, camera: &Camera, j: u32) -> Vec<Color> {
+        (0..self.image_width)
+            .map(|i| self.render_pixel(world, camera, i, j, &mut RenderStats::default()))
+            .collect()
+    }
+
+    // `j` counts scanlines from the bottom, like the camera's v coordinate.
+    fn render_pixel(
+        &self,
+        world: &impl Hittable,
+        camera: &Camera,
+        i: u32,
+        j: u32,
+        stats: &mut RenderStats,
+    ) -> Color {
+        seed_rng(self.pixel_seed(i, j));
+
+        let mut pixel_color = COLOR_BLACK;
+        for offset in self.sampler.pixel_offsets(self.samples_per_pixel) {
+            pixel_color += self.sample(world, camera, i, j, offset, stats);
+        }
+        // keep returning sums, so the writers can divide by the sample count as usual
+        let samples = self.samples_per_pixel as Float;
+        self.tone_map.apply(pixel_color / samples) * samples
+    }
+
+    // The global seed hashed with the pixel's coordinates, so each pixel draws the same
+    // samples whichever thread or tile renders it.
+    fn pixel_seed(&self, i: u32, j: u32) -> u64 {
+        mix_seed(mix_seed(self.seed, j as u64), i as u64)
+    }
+
+    // One sample through pixel (i, j) at `(du, dv)` within it, before tone mapping.
+    fn sample(
+        &self,
+        world: &impl Hittable,
+        camera: &Camera,
+        i: u32,
+        j: u32,
+        (du, dv): (Float, Float),
+        stats: &mut RenderStats,
+    ) -> Color {
+        let u = (i as Float + du) / (self.image_width as Float - 1.0); // how horizontal? (0 to 1)
+        let v = (j as Float + dv) / (self.image_height as Float - 1.0); // how vertical? (0 to 1)
+        let ray = camera.get_ray(u, v);
+        self.clamp_firefly(self.trace(world, ray, stats).sanitized() * camera.exposure())
+    }
+
+    // Scales `sample` down to the `firefly_clamp` luminance if it's brighter, keeping its hue.
+    fn clamp_firefly(&self, sample: Color) -> Color {
+        match self.firefly_clamp {
+            Some(max) if sample.luminance() > max => sample * (max / sample.luminance()),
+            _ => sample,
+        }
+    }
+}
+
+// Running per-pixel color sums kept in single precision, with a sample count for each
+// pixel: half the memory of `Color` sums in the default (f64) build.
+pub struct SampleBuffer {
+    sums: Vec<[f32; 3]>,
+    counts: Vec<u32>,
+}
+
+impl SampleBuffer {
+    pub fn new(pixel_count: usize) -> Self {
+        SampleBuffer {
+            sums: vec![[0.0; 3]; pixel_count],
+            counts: vec![0; pixel_count],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sums.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sums.is_empty()
+    }
+
+    // the cast is a no-op in the f32 build
+    #[allow(clippy::unnecessary_cast)]
+    pub fn add_sample(&mut self, pixel: usize, color: Color) {
+        let sum = &mut self.sums[pixel];
+        for (channel, value) in sum.iter_mut().zip([color.x(), color.y(), color.z()]) {
+            *channel += value as f32;
+        }
+        self.counts[pixel] += 1;
+    }
+
+    pub fn sample_count(&self, pixel: usize) -> u32 {
+        self.counts[pixel]
+    }
+
+    // The mean of the pixel's samples, or black if it has none.
+    pub fn resolve(&self, pixel: usize) -> Color {
+        let [r, g, b] = self.sums[pixel];
+        let count = self.counts[pixel].max(1) as Float;
+        Color::new(r as Float, g as Float, b as Float) / count
+    }
+}
+
+// Builds up an image one sample per pixel at a time, for progressive display.
+pub struct Accumulator {
+    // only its image size, seed and per-sample settings are used
+    pub renderer: Renderer,
+    // ordered top-to-bottom then left-to-right
+    buffer: SampleBuffer,
+    samples: u32,
+}
+
+impl Accumulator {
+    pub fn new(renderer: Renderer) -> Self {
+        let pixel_count = (renderer.image_width * renderer.image_height) as usize;
+        Accumulator {
+            renderer,
+            buffer: SampleBuffer::new(pixel_count),
+            samples: 0,
+        }
+    }
+
+    // Number of passes added so far.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    // Adds one more sample to every pixel.
+    pub fn add_pass(&mut self, world: &impl Hittable, camera: &Camera) {
+        let renderer = &self.renderer;
+        let pass = self.samples as u64;
+        let pass_samples: Vec<Color> = (0..renderer.image_height)
+            .into_par_iter()
+            .rev()
+            .flat_map_iter(|j| {
+                (0..renderer.image_width).map(move |i| {
+                    // seeded per pass as well, so every pass draws fresh samples
+                    seed_rng(mix_seed(renderer.pixel_seed(i, j), pass));
+                    let offset = renderer.sampler.pixel_offsets(1)[0];
+                    renderer.sample(world, camera, i, j, offset, &mut RenderStats::default())
+                })
+            })
+            .collect();
+
+        for (pixel, sample) in pass_samples.into_iter().enumerate() {
+            self.buffer.add_sample(pixel, sample);
+        }
+        self.samples += 1;
+    }
+
+    // The image so far as tone-mapped, gamma-corrected RGB bytes; black before the first pass.
+    pub fn resolve(&self) -> Vec<u8> {
+        (0..self.buffer.len())
+            .flat_map(|pixel| {
+                let mean = self.buffer.resolve(pixel);
+                self.renderer.tone_map.apply(mean).to_rgb8(1)
+            })
+            .collect()
+    }
+}
+
+// Writes an ASCII (P3) PPM image.
+// `pixels` are accumulated color sums, ordered top-to-bottom then left-to-right.
+pub fn write_ppm<W: Write>(
+    writer: &mut W,
+    pixels: &[Color],
+    width: u32,
+    height: u32,
+    samples: u32,
+) -> io::Result<()> {
+    check_pixel_count(pixels, width, height)?;
+
+    // colors are in ascii
+    writeln!(writer, "P3")?;
+    // columns, rows
+    writeln!(writer, "{width} {height}")?;
+    // max color
+    writeln!(writer, "255")?;
+
+    // RGB triplets
+    for pixel in pixels {
+        let [ir, ig, ib] = pixel.to_rgb8(samples);
+        writeln!(writer, "{ir} {ig} {ib}")?;
+    }
+    Ok(())
+}
+
+// Writes a binary (P6) PPM image: the same header as `write_ppm`, then raw RGB bytes.
+// `pixels` are accumulated color sums, ordered top-to-bottom then left-to-right.
+pub fn write_ppm_binary<W: Write>(
+    writer: &mut W,
+    pixels: &[Color],
+    width: u32,
+    height: u32,
+    samples: u32,
+) -> io::Result<()> {
+    check_pixel_count(pixels, width, height)?;
+
+    write!(writer, "P6\n{width} {height}\n255\n")?;
+    let bytes: Vec<u8> = pixels.iter().flat_map(|p| p.to_rgb8(samples)).collect();
+    writer.write_all(&bytes)
+}
+
+fn check_pixel_count(pixels: &[Color], width: u32, height: u32) -> io::Result<()> {
+    if pixels.len() != (width * height) as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "expected {} pixels for a {width}x{height} image, got {}",
+                width * height,
+                pixels.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Writes a PNG image (or any other format `image` infers from the path's extension).
+// `pixels` are accumulated color sums, ordered top-to-bottom then left-to-right.
+#[cfg(feature = "png")]
+pub fn write_png(
+    path: &Path,
+    pixels: &[Color],
+    width: u32,
+    height: u32,
+    samples: u32,
+) -> Result<(), ImageError> {
+    if pixels.len() != (width * height) as usize {
+        return Err(ImageError::Parameter(ParameterError::from_kind(
+            ParameterErrorKind::DimensionMismatch,
+        )));
+    }
+
+    let img = RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb(pixels[(y * width + x) as usize].to_rgb8(samples))
+    });
+    img.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::background::{GradientSky, SolidBackground};
+    use crate::material::{Dialectric, DiffuseLight, Lambertian, Material, Metal, Phong};
+    use crate::plane::Plane;
+    use crate::quad::Quad;
+    use crate::rect::XyRect;
+    use crate::tolerance;
+    use crate::vec3::Point3;
+    use crate::{HitList, Sphere};
+
+    fn test_scene() -> (HitList, Camera) {
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, -100.5, -1.0),
+            100.0,
+            Arc::new(Lambertian::new(Color::new(0.8, 0.8, 0.0))),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -1.0),
+            0.5,
+            Arc::new(Metal::new(Color::new(0.8, 0.6, 0.2), 0.3)),
+        )));
+        let camera = Camera::new(
+            Point3::new(0.0, 0.0, 1.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            60.0,
+            4.0 / 3.0,
+            0.1,
+            2.0,
+        );
+        (world, camera)
+    }
+
+    fn test_renderer() -> Renderer {
+        Renderer {
+            samples_per_pixel: 4,
+            max_depth: 10,
+            ..Renderer::new(8, 6)
+        }
+    }
+
+    #[test]
+    fn test_indirect_background_scale_keeps_sky_out_of_box() {
+        // a white box, open toward the camera, with a small lamp at the back of its ceiling
+        let white: Arc<dyn Material> = Arc::new(Lambertian::new(Color::new(0.7, 0.7, 0.7)));
+        let mut world = HitList::new();
+        let walls = [
+            // back, floor, ceiling, left, right
+            (
+                Point3::new(-1.0, -1.0, -2.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 2.0, 0.0),
+            ),
+            (
+                Point3::new(-1.0, -1.0, -2.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+            ),
+            (
+                Point3::new(-1.0, 1.0, -2.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+            ),
+            (
+                Point3::new(-1.0, -1.0, -2.0),
+                Vec3::new(0.0, 2.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+            ),
+            (
+                Point3::new(1.0, -1.0, -2.0),
+                Vec3::new(0.0, 2.0, 0.0),
+                Vec3::new(0.0, 0.0, 2.0),
+            ),
+        ];
+        for (q, u, v) in walls {
+            world.add(Box::new(Quad::new(q, u, v, Arc::clone(&white))));
+        }
+        let lamp = Point3::new(0.0, 0.999, -1.8);
+        world.add(Box::new(Quad::new(
+            lamp - Vec3::new(0.1, 0.0, 0.1),
+            Vec3::new(0.2, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 0.2),
+            Arc::new(DiffuseLight::new(Color::new(20.0, 20.0, 20.0))),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 2.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            60.0,
+            1.0,
+        );
+        let render = |indirect_background_scale| {
+            let renderer = Renderer {
+                samples_per_pixel: 32,
+                max_depth: 8,
+                background: Arc::new(SolidBackground(COLOR_WHITE)),
+                indirect_background_scale,
+                sampler: Sampler::Stratified,
+                ..Renderer::new(12, 12)
+            };
+            renderer.render(&world, &camera)
+        };
+        let (leaky, sealed) = (render(1.0), render(0.0));
+
+        // sort pixels, indexed like the render, by what they see
+        let (mut sky, mut far_from_lamp, mut near_lamp) = (Vec::new(), Vec::new(), Vec::new());
+        for index in 0..144 {
+            let (i, j) = ((index % 12) as Float, (11 - index / 12) as Float);
+            let sees = |du: Float, dv: Float| {
+                world.hit(
+                    &camera.get_ray((i + du) / 11.0, (j + dv) / 11.0),
+                    0.001,
+                    Float::INFINITY,
+                )
+            };
+            let corners = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)];
+            if corners.iter().all(|&(du, dv)| sees(du, dv).is_none()) {
+                sky.push(index);
+            } else if let Some(rec) = sees(0.5, 0.5) {
+                if (rec.p - lamp).length() < 0.6 {
+                    near_lamp.push(index);
+                } else {
+                    far_from_lamp.push(index);
+                }
+            }
+        }
+        let mean = |pixels: &[Color], indices: &[usize]| {
+            indices
+                .iter()
+                .map(|&i| pixels[i].luminance())
+                .sum::<Float>()
+                / indices.len() as Float
+        };
+
+        // camera rays still see the sky
+        assert!(!sky.is_empty());
+        assert_eq!(mean(&leaky, &sky), mean(&sealed, &sky));
+        // but sky light no longer leaks in to brighten the walls
+        let (leaky_far, sealed_far) = (mean(&leaky, &far_from_lamp), mean(&sealed, &far_from_lamp));
+        assert!(
+            sealed_far < 0.5 * leaky_far,
+            "walls at {sealed_far} sealed, {leaky_far} with sky light"
+        );
+        // the lamp's surroundings stay lit
+        let sealed_near = mean(&sealed, &near_lamp);
+        assert!(
+            sealed_near > 2.0 * sealed_far,
+            "{sealed_near} by the lamp, {sealed_far} away"
+        );
+    }
+
+    #[test]
+    fn test_firefly_clamp_limits_bright_samples() {
+        let clamped = Renderer {
+            firefly_clamp: Some(4.0),
+            ..test_renderer()
+        };
+        let firefly = Color::new(1000.0, 1000.0, 500.0);
+        let limited = clamped.clamp_firefly(firefly);
+        assert!((limited.luminance() - 4.0).abs() < tolerance(1e-9));
+        assert!((limited.z() / limited.x() - 0.5).abs() < tolerance(1e-9));
+        let dim = Color::new(0.5, 0.5, 0.5);
+        assert_eq!(clamped.clamp_firefly(dim), dim);
+
+        // a pixel that sees nothing but a very bright lamp
+        let mut world = HitList::new();
+        world.add(Box::new(XyRect::new(
+            -100.0,
+            100.0,
+            -100.0,
+            100.0,
+            -1.0,
+            Arc::new(DiffuseLight::new(Color::new(1000.0, 1000.0, 1000.0))),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            4.0 / 3.0,
+        );
+        let samples = clamped.samples_per_pixel as Float;
+        let pixel = |renderer: &Renderer| renderer.render(&world, &camera)[0].luminance() / samples;
+        assert!((pixel(&clamped) - 4.0).abs() < tolerance(1e-9));
+        assert!((pixel(&test_renderer()) - 1000.0).abs() < tolerance(1e-9));
+
+        // the preview clamps its center rays as well as its silhouette rays
+        let preview = clamped.render_preview(&world, &camera);
+        assert!(preview
+            .pixels
+            .iter()
+            .all(|p| (p.luminance() - 4.0).abs() < tolerance(1e-9)));
+    }
+
+    #[test]
+    fn test_render_single_light() {
+        // a light panel covering the right half of the view
+        let mut world = HitList::new();
+        world.add(Box::new(XyRect::new(
+            0.0,
+            100.0,
+            -100.0,
+            100.0,
+            -1.0,
+            Arc::new(DiffuseLight::new(Color::new(2.0, 2.0, 2.0))),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            4.0 / 3.0,
+        );
+        let renderer = Renderer {
+            samples_per_pixel: 3,
+            background: Arc::new(SolidBackground(COLOR_BLACK)),
+            ..Renderer::new(4, 3)
+        };
+
+        let pixels = renderer.render(&world, &camera);
+        assert_eq!(pixels.len(), 4 * 3);
+        // left columns see only the background, right columns see the light
+        assert_eq!(pixels[0], COLOR_BLACK);
+        assert_eq!(pixels[8], COLOR_BLACK);
+        assert_eq!(pixels[3], Color::new(6.0, 6.0, 6.0));
+        assert_eq!(pixels[10], Color::new(6.0, 6.0, 6.0));
+    }
+
+    #[test]
+    fn test_exposure_scales_render() {
+        let (world, camera) = test_scene();
+        let renderer = test_renderer();
+        let plain = renderer.render(&world, &camera);
+        let doubled = renderer.render(&world, &camera.with_exposure(2.0));
+        for (p, d) in plain.iter().zip(&doubled) {
+            assert!((*p * 2.0 - *d).length() < tolerance(1e-9));
+        }
+    }
+
+    #[test]
+    fn test_render_tone_mapped_light() {
+        let mut world = HitList::new();
+        world.add(Box::new(XyRect::new(
+            -100.0,
+            100.0,
+            -100.0,
+            100.0,
+            -1.0,
+            Arc::new(DiffuseLight::new(Color::new(4.0, 4.0, 4.0))),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            4.0 / 3.0,
+        );
+        let renderer = Renderer {
+            samples_per_pixel: 2,
+            background: Arc::new(SolidBackground(COLOR_BLACK)),
+            tone_map: ToneMap::Reinhard,
+            ..Renderer::new(4, 3)
+        };
+
+        let pixels = renderer.render(&world, &camera);
+        for p in pixels {
+            assert!((p / 2.0 - Color::new(0.8, 0.8, 0.8)).length() < tolerance(1e-12));
+        }
+    }
+
+    #[test]
+    fn test_stratified_offsets_cover_every_cell() {
+        seed_rng(7);
+        let offsets = Sampler::Stratified.pixel_offsets(16);
+        assert_eq!(offsets.len(), 16);
+
+        let mut cells = [0; 16];
+        for (du, dv) in offsets {
+            assert!((0.0..1.0).contains(&du) && (0.0..1.0).contains(&dv));
+            cells[(dv * 4.0) as usize * 4 + (du * 4.0) as usize] += 1;
+        }
+        assert_eq!(cells, [1; 16]);
+    }
+
+    #[test]
+    fn test_stratified_offsets_fill_leftover_samples() {
+        let offsets = Sampler::Stratified.pixel_offsets(5);
+        assert_eq!(offsets.len(), 5);
+        assert_eq!(Sampler::Uniform.pixel_offsets(5).len(), 5);
+    }
+
+    #[test]
+    fn test_render_aovs_centered_sphere() {
+        let mut world = HitList::new();
+        let albedo = Color::new(0.2, 0.4, 0.6);
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -2.0),
+            0.5,
+            Arc::new(Lambertian::new(albedo)),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+        );
+        let renderer = Renderer {
+            samples_per_pixel: 2,
+            max_depth: 4,
+            ..Renderer::new(5, 5)
+        };
+
+        let aovs = renderer.render_aovs(&world, &camera);
+        assert_eq!(aovs.color, renderer.render(&world, &camera));
+        assert_eq!(aovs.normal.len(), 25);
+        assert_eq!(aovs.depth.len(), 25);
+        assert_eq!(aovs.albedo.len(), 25);
+
+        let center = 2 * 5 + 2;
+        assert!((aovs.normal[center] - Vec3::new(0.0, 0.0, 1.0)).length() < tolerance(1e-9));
+        assert!((aovs.depth[center] - 1.5).abs() < tolerance(1e-9));
+        assert_eq!(aovs.albedo[center], albedo);
+
+        // corners miss the sphere
+        assert_eq!(aovs.depth[0], Float::INFINITY);
+        let corner = camera.get_ray(0.0, 1.0);
+        assert_eq!(aovs.albedo[0], renderer.background.color(&corner));
+    }
+
+    #[test]
+    fn test_render_shade_normals() {
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, -3.0),
+            1.0,
+            Arc::new(Metal::new(COLOR_WHITE, 0.0)),
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            10.0,
+            1.0,
+        );
+        let renderer = Renderer {
+            samples_per_pixel: 4,
+            mode: RenderMode::ShadeNormals,
+            ..Renderer::new(5, 5)
+        };
+
+        let pixels = renderer.render(&world, &camera);
+        let center = pixels[2 * 5 + 2] / 4.0;
+        assert!(
+            (center - Color::new(0.5, 0.5, 1.0)).length() < 0.05,
+            "{center:?}"
+        );
+    }
+
+    #[test]
+    fn test_render_with_sun() {
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(2.0, 1.0, 0.0),
+            0.5,
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        let sun = DirectionalLight::new(Vec3::new(0.0, -1.0, 0.0), Color::new(3.0, 3.0, 3.0));
+
+        let looking_down_at = |x: Float| {
+            let r = Ray::new(Point3::new(x, 0.2, 0.0), Vec3::new(0.0, -1.0, 0.0));
+            r.color_with_lights(&world, &[sun], &[], &SolidBackground(COLOR_BLACK), 1)
+        };
+        let lit = looking_down_at(0.0);
+        let shadowed = looking_down_at(2.0);
+        // 3 * 0.5 * cos(0) / pi
+        assert!((lit - Color::new(1.5, 1.5, 1.5) / crate::consts::PI).length() < tolerance(1e-9));
+        assert_eq!(shadowed, COLOR_BLACK);
+
+        let renderer = Renderer {
+            samples_per_pixel: 1,
+            background: Arc::new(SolidBackground(COLOR_BLACK)),
+            lights: vec![sun],
+            ..Renderer::new(2, 2)
+        };
+        let camera = Camera::look_at(
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            10.0,
+            1.0,
+        );
+        let pixels = renderer.render(&world, &camera);
+        assert!(pixels.iter().all(|p| p.x() > 0.3));
+    }
+
+    #[test]
+    fn test_glass_sphere_inverts_background() {
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(Dialectric::new(1.5)),
+        )));
+        // red below the horizon, blue above
+        let renderer = Renderer {
+            samples_per_pixel: 16,
+            max_depth: 10,
+            background: Arc::new(GradientSky::new(
+                Color::new(1.0, 0.0, 0.0),
+                Color::new(0.0, 0.0, 1.0),
+            )),
+            ..Renderer::new(20, 20)
+        };
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            30.0,
+            1.0,
+        );
+        let pixels = renderer.render(&world, &camera);
+        let average = |rows: std::ops::Range<usize>| {
+            let pixels = &pixels;
+            let region: Vec<Color> = rows
+                .flat_map(|j| (8..12).map(move |i| pixels[j * 20 + i]))
+                .collect();
+            region.iter().fold(COLOR_BLACK, |sum, &p| sum + p) / region.len() as Float
+        };
+
+        // a correctly refracting ball is a lens: it shows the sky upside down
+        let upper = average(5..8);
+        let lower = average(12..15);
+        assert!(upper.x() > upper.z(), "upper half of the ball is {upper:?}");
+        assert!(lower.z() > lower.x(), "lower half of the ball is {lower:?}");
+        // and the background around it is the right way up
+        assert!(pixels[10].z() > pixels[10].x());
+        assert!(pixels[19 * 20 + 10].x() > pixels[19 * 20 + 10].z());
+    }
+
+    #[test]
+    fn test_render_matches_in_either_float_mode() {
+        // sunlight on a floor, with no bounces: every pixel is albedo * sun * cos / pi,
+        // whatever the sample positions, so f32 and f64 renders agree byte for byte
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.8, 0.4, 0.2))),
+        )));
+        let renderer = Renderer {
+            samples_per_pixel: 4,
+            max_depth: 1,
+            background: Arc::new(SolidBackground(COLOR_BLACK)),
+            lights: vec![DirectionalLight::new(
+                Vec3::new(1.0, -2.0, 0.0),
+                Color::new(2.0, 2.0, 2.0),
+            )],
+            ..Renderer::new(4, 4)
+        };
+        let camera = Camera::look_at(
+            Point3::new(0.0, 3.0, 3.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            30.0,
+            1.0,
+        );
+        let pixels = renderer.render(&world, &camera);
+        // 2 * (0.8, 0.4, 0.2) * (2 / sqrt(5)) / pi, gamma corrected
+        for p in &pixels {
+            assert_eq!(p.to_rgb8(4), [172, 122, 86]);
+        }
+    }
+
+    #[test]
+    fn test_light_sampling_reduces_variance() {
+        let lamp = Arc::new(Sphere::new(
+            Point3::new(0.0, 3.0, 0.0),
+            0.2,
+            Arc::new(DiffuseLight::new(Color::new(50.0, 50.0, 50.0))),
+        ));
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(Box::new(Sphere::new(
+            lamp.center,
+            lamp.radius,
+            Arc::clone(&lamp.mat_ptr),
+        )));
+
+        // mean and variance of one-bounce estimates of the light reaching a ground point;
+        // f32 draws a different random stream, where the lamp's rare hits need more
+        // samples to average out
+        let count = if cfg!(feature = "f32") { 16000 } else { 4000 };
+        let estimate = |area_lights: &[Arc<dyn PdfHittable>]| {
+            seed_rng(5);
+            let samples: Vec<Float> = (0..count)
+                .map(|_| {
+                    let r = Ray::new(Point3::new(0.5, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+                    r.color_with_lights(&world, &[], area_lights, &SolidBackground(COLOR_BLACK), 2)
+                        .x()
+                })
+                .collect();
+            let mean = samples.iter().sum::<Float>() / samples.len() as Float;
+            let variance =
+                samples.iter().map(|s| (s - mean).powi(2)).sum::<Float>() / samples.len() as Float;
+            (mean, variance)
+        };
+
+        let (brute_mean, brute_variance) = estimate(&[]);
+        let (sampled_mean, sampled_variance) = estimate(&[lamp]);
+        assert!(
+            sampled_variance < 0.1 * brute_variance,
+            "variance {sampled_variance} with light sampling, {brute_variance} without"
+        );
+        // both estimate the same thing
+        assert!(
+            (sampled_mean - brute_mean).abs() < 0.3 * sampled_mean,
+            "mean {sampled_mean} with light sampling, {brute_mean} without"
+        );
+    }
+
+    #[test]
+    fn test_mis_beats_either_strategy() {
+        // a small bright lamp, which the floor's bounces rarely find, and a large dim one
+        // in the mirror direction, whose sampled directions mostly miss the glossy lobe
+        let small = Arc::new(Sphere::new(
+            Point3::new(1.0, 1.0, 1.0),
+            0.05,
+            Arc::new(DiffuseLight::new(Color::new(400.0, 400.0, 400.0))),
+        ));
+        let large = Arc::new(Sphere::new(
+            Point3::new(-1.0, 4.0, -3.0),
+            1.5,
+            Arc::new(DiffuseLight::new(Color::new(1.0, 1.0, 1.0))),
+        ));
+        let area_lights: Vec<Arc<dyn PdfHittable>> = vec![small.clone(), large.clone()];
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Phong::new(
+                Color::new(0.2, 0.2, 0.2),
+                Color::new(0.6, 0.6, 0.6),
+                200.0,
+            )),
+        )));
+        for lamp in [&small, &large] {
+            world.add(Box::new(Sphere::new(
+                lamp.center,
+                lamp.radius,
+                Arc::clone(&lamp.mat_ptr),
+            )));
+        }
+
+        // mean and variance of one-bounce estimates of the light leaving a floor point;
+        // more samples under f32, as in `test_light_sampling_reduces_variance`
+        let count = if cfg!(feature = "f32") { 80000 } else { 20000 };
+        let estimate = |strategy: LightStrategy| {
+            let ctx = PathContext {
+                world: &world,
+                lights: &[],
+                point_lights: &[],
+                spot_lights: &[],
+                area_lights: &area_lights,
+                background: &SolidBackground(COLOR_BLACK),
+                strategy,
+                primary_depth: 2,
+                indirect_background_scale: 1.0,
+            };
+            seed_rng(9);
+            let samples: Vec<Float> = (0..count)
+                .map(|_| {
+                    let r = Ray::new(Point3::new(-1.0, 1.0, 2.0), Vec3::new(0.0, -1.0, -1.0));
+                    r.trace_path(&ctx, 2, None, &mut RenderStats::default()).x()
+                })
+                .collect();
+            let mean = samples.iter().sum::<Float>() / samples.len() as Float;
+            let variance =
+                samples.iter().map(|s| (s - mean).powi(2)).sum::<Float>() / samples.len() as Float;
+            (mean, variance)
+        };
+
+        let (brdf_mean, brdf_variance) = estimate(LightStrategy::Brdf);
+        let (light_mean, light_variance) = estimate(LightStrategy::Light);
+        let (mis_mean, mis_variance) = estimate(LightStrategy::Mis);
+        assert!(
+            mis_variance < brdf_variance && mis_variance < light_variance,
+            "variance {mis_variance} with MIS, {brdf_variance} BRDF only, {light_variance} lights only"
+        );
+        // all three estimate the same thing
+        for mean in [brdf_mean, light_mean] {
+            assert!(
+                (mean - mis_mean).abs() < 0.1 * mis_mean,
+                "mean {mis_mean} with MIS, {brdf_mean} BRDF only, {light_mean} lights only"
+            );
+        }
+    }
+
+    #[test]
+    fn test_light_sampling_counts_unlisted_emitters_in_front() {
+        // the floor sees the lamp partly behind a glowing ball that isn't an area light
+        let lamp = Arc::new(Sphere::new(
+            Point3::new(0.0, 3.0, 0.0),
+            0.6,
+            Arc::new(DiffuseLight::new(Color::new(10.0, 10.0, 10.0))),
+        ));
+        let area_lights: Vec<Arc<dyn PdfHittable>> = vec![lamp.clone()];
+        let mut world = HitList::new();
+        world.add(Box::new(Plane::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(Color::new(0.5, 0.5, 0.5))),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 1.5, 0.0),
+            0.2,
+            Arc::new(DiffuseLight::new(Color::new(40.0, 40.0, 40.0))),
+        )));
+        world.add(Box::new(Sphere::new(
+            lamp.center,
+            lamp.radius,
+            Arc::clone(&lamp.mat_ptr),
+        )));
+
+        let mean = |strategy: LightStrategy| {
+            let ctx = PathContext {
+                world: &world,
+                lights: &[],
+                point_lights: &[],
+                spot_lights: &[],
+                area_lights: &area_lights,
+                background: &SolidBackground(COLOR_BLACK),
+                strategy,
+                primary_depth: 2,
+                indirect_background_scale: 1.0,
+            };
+            seed_rng(11);
+            let n = 40000;
+            (0..n)
+                .map(|_| {
+                    let r = Ray::new(Point3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0));
+                    r.trace_path(&ctx, 2, None, &mut RenderStats::default()).x()
+                })
+                .sum::<Float>()
+                / n as Float
+        };
+
+        // every strategy finds the same light, the ball's included
+        let brdf = mean(LightStrategy::Brdf);
+        for strategy in [LightStrategy::Light, LightStrategy::Mis] {
+            let sampled = mean(strategy);
+            assert!(
+                (sampled - brdf).abs() < 0.1 * brdf,
+                "mean {sampled} with {strategy:?}, {brdf} BRDF only"
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_preview_refines_only_silhouettes() {
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3::new(0.0, 0.0, 0.0),
+            1.0,
+            Arc::new(DiffuseLight::new(COLOR_WHITE)),
+        )));
+        let renderer = Renderer {
+            background: Arc::new(SolidBackground(COLOR_BLACK)),
+            ..Renderer::new(16, 16)
+        };
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 4.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            45.0,
+            1.0,
+        );
+        let preview = renderer.render_preview(&world, &camera);
+        let at = |x: usize, y: usize| (preview.pixels[y * 16 + x], preview.samples[y * 16 + x]);
+
+        // inside the sphere and out in the background, one ray is enough
+        assert_eq!(at(8, 8), (COLOR_WHITE, 1));
+        assert_eq!(at(0, 0), (COLOR_BLACK, 1));
+        // the silhouette gets extra rays, which blend the two
+        let edges: Vec<usize> = (0..256).filter(|&p| preview.samples[p] > 1).collect();
+        assert!(!edges.is_empty() && edges.len() < 128);
+        assert!(edges
+            .iter()
+            .all(|&p| preview.samples[p] == 1 + PREVIEW_EDGE_SAMPLES));
+        assert!(edges
+            .iter()
+            .any(|&p| preview.pixels[p].x() > 0.0 && preview.pixels[p].x() < 1.0));
+    }
+
+    #[test]
+    fn test_render_tiled_matches_full_frame() {
+        let (world, camera) = test_scene();
+        let renderer = test_renderer();
+        let full = renderer.render(&world, &camera);
+
+        let mut tiles = 0;
+        let mut reassembled = vec![COLOR_BLACK; full.len()];
+        renderer.render_tiled(&world, &camera, 3, |tile| {
+            tiles += 1;
+            assert_eq!(tile.pixels.len(), (tile.width * tile.height) as usize);
+            for (k, pixel) in tile.pixels.into_iter().enumerate() {
+                let x = tile.x + k as u32 % tile.width;
+                let y = tile.y + k as u32 / tile.width;
+                reassembled[(y * renderer.image_width + x) as usize] = pixel;
+            }
+        });
+
+        // an 8x6 image in 3x3 tiles
+        assert_eq!(tiles, 3 * 2);
+        assert_eq!(reassembled, full);
+    }
+
+    #[test]
+    fn test_render_region_matches_full_frame() {
+        let (world, camera) = test_scene();
+        let renderer = test_renderer();
+        let full = renderer.render(&world, &camera);
+
+        let (x0, y0, x1, y1) = (2, 1, 7, 4);
+        let crop = renderer.render_region(&world, &camera, x0, y0, x1, y1);
+        let expected: Vec<Color> = (y0..y1)
+            .flat_map(|y| (x0..x1).map(move |x| (y * renderer.image_width + x) as usize))
+            .map(|k| full[k])
+            .collect();
+        assert_eq!(crop, expected);
+
+        assert!(renderer
+            .render_region(&world, &camera, 3, 2, 3, 5)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_render_stats_count_depth_exhaustion() {
+        // two facing mirrors, with the camera between them
+        let mirror = Arc::new(Metal::new(Color::new(0.9, 0.9, 0.9), 0.0));
+        let mut world = HitList::new();
+        world.add(Box::new(Quad::new(
+            Point3::new(-1.0, -1.0, -1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            mirror.clone(),
+        )));
+        world.add(Box::new(Quad::new(
+            Point3::new(-1.0, -1.0, 1.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            mirror,
+        )));
+        let camera = Camera::look_at(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            1.0,
+        );
+        let stats_at_depth = |max_depth| {
+            let renderer = Renderer {
+                samples_per_pixel: 4,
+                max_depth,
+                ..Renderer::new(10, 10)
+            };
+            let (pixels, stats) = renderer.render_with_stats(&world, &camera);
+            assert_eq!(pixels, renderer.render(&world, &camera));
+            stats
+        };
+
+        let shallow = stats_at_depth(1);
+        let deep = stats_at_depth(50);
+        // nearly every camera ray hits a mirror and gets cut off after one bounce
+        assert!(shallow.depth_exhausted > 300, "{shallow:?}");
+        assert_eq!(shallow.rays_traced, 400);
+        assert!(
+            deep.depth_exhausted < shallow.depth_exhausted / 5,
+            "{deep:?}"
+        );
+        assert!(deep.rays_traced > shallow.rays_traced);
+    }
+
+    #[test]
+    fn test_sample_buffer_matches_double_precision_mean() {
+        seed_rng(6);
+        let mut buffer = SampleBuffer::new(2);
+        let mut sum = COLOR_BLACK;
+        for _ in 0..10000 {
+            let sample = Color::new(random_double(), 2.0 * random_double(), 0.1);
+            buffer.add_sample(1, sample);
+            sum += sample;
+        }
+        assert_eq!((buffer.sample_count(0), buffer.sample_count(1)), (0, 10000));
+        assert_eq!(buffer.resolve(0), COLOR_BLACK);
+
+        let mean = sum / 10000.0;
+        let error = buffer.resolve(1) - mean;
+        assert!(
+            error.length() < 1e-4 * mean.length(),
+            "resolved {:?}, expected {mean:?}",
+            buffer.resolve(1)
+        );
+    }
+
+    #[test]
+    fn test_accumulator_converges_to_full_render() {
+        let (world, camera) = test_scene();
+        let renderer = Renderer {
+            samples_per_pixel: 64,
+            ..test_renderer()
+        };
+        let reference: Vec<u8> = renderer
+            .render(&world, &camera)
+            .iter()
+            .flat_map(|p| p.to_rgb8(64))
+            .collect();
+
+        let mut accumulator = Accumulator::new(test_renderer());
+        assert!(accumulator.resolve().iter().all(|&b| b == 0));
+        let mut errors = Vec::new();
+        for _ in 0..64 {
+            accumulator.add_pass(&world, &camera);
+            let error = accumulator
+                .resolve()
+                .iter()
+                .zip(&reference)
+                .map(|(&a, &b)| (a as Float - b as Float).abs())
+                .sum::<Float>()
+                / reference.len() as Float;
+            errors.push(error);
+        }
+        assert_eq!(accumulator.samples(), 64);
+
+        // the noise shrinks as passes accumulate, down to roughly a 64-sample render's
+        assert!(
+            errors[63] < errors[3] && errors[3] < errors[0],
+            "{errors:?}"
+        );
+        assert!(errors[63] < 3.0, "mean byte difference {}", errors[63]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_render_parallel_matches_serial() {
+        let (world, camera) = test_scene();
+        let renderer = test_renderer();
+        let serial = renderer.render(&world, &camera);
+        assert_eq!(
+            serial.len(),
+            (renderer.image_width * renderer.image_height) as usize
+        );
+
+        // enough threads that rows are spread across them in whatever order they finish
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        for _ in 0..3 {
+            let parallel = pool.install(|| renderer.render_parallel(&world, &camera));
+            assert_eq!(parallel, serial);
+        }
+    }
+
+    #[test]
+    fn test_render_is_reproducible_with_seed() {
+        let (world, camera) = test_scene();
+        let render_ppm = |seed| {
+            let renderer = Renderer {
+                seed,
+                ..test_renderer()
+            };
+            let pixels = renderer.render_parallel(&world, &camera);
+            let mut out = Vec::new();
+            write_ppm(&mut out, &pixels, 8, 6, 4).unwrap();
+            out
+        };
+
+        assert_eq!(render_ppm(42), render_ppm(42));
+        assert_ne!(render_ppm(42), render_ppm(43));
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let pixels = [COLOR_WHITE, COLOR_BLACK, COLOR_BLACK, COLOR_WHITE];
+        let mut out = Vec::new();
+        write_ppm(&mut out, &pixels, 2, 2, 1).unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(&lines[..3], &["P3", "2 2", "255"]);
+        assert_eq!(
+            &lines[3..],
+            &["255 255 255", "0 0 0", "0 0 0", "255 255 255"]
+        );
+    }
+
+    #[test]
+    fn test_write_ppm_binary() {
+        let pixels = [
+            COLOR_WHITE,
+            COLOR_BLACK,
+            Color::new(1.0, 0.25, 0.0),
+            COLOR_WHITE,
+        ];
+        let mut out = Vec::new();
+        write_ppm_binary(&mut out, &pixels, 2, 2, 1).unwrap();
+
+        let header = b"P6\n2 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let body = &out[header.len()..];
+        assert_eq!(body.len(), 2 * 2 * 3);
+        // the third pixel, gamma corrected
+        assert_eq!(&body[6..9], &[255, 128, 0]);
+
+        let mut ascii = Vec::new();
+        write_ppm(&mut ascii, &pixels, 2, 2, 1).unwrap();
+        assert!(out.len() < ascii.len());
+        assert!(write_ppm_binary(&mut out, &pixels[..3], 2, 2, 1).is_err());
+    }
+
+    #[test]
+    fn test_write_ppm_rejects_wrong_pixel_count() {
+        let mut out = Vec::new();
+        let err = write_ppm(&mut out, &[COLOR_WHITE; 3], 2, 2, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_write_png() {
+        let (width, height) = (4, 3);
+        let pixels: Vec<Color> = (0..width * height)
+            .map(|i| Color::new((i % width) as Float / (width - 1) as Float, 0.0, 0.25))
+            .collect();
+        let path = std::env::temp_dir().join("rt_test_write_png.png");
+        write_png(&path, &pixels, width, height, 1).unwrap();
+
+        let img = image::open(&path).unwrap().to_rgb8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(img.dimensions(), (width, height));
+        assert_eq!(img.get_pixel(0, 1).0, [0, 0, 128]);
+        assert_eq!(img.get_pixel(3, 2).0, [255, 0, 128]);
+    }
+}
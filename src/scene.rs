@@ -3,6 +3,7 @@ use std::sync::Arc;
 use rt::material::{Dialectric, Lambertian, Material, Metal};
 use rt::util::{random_bounded, random_double};
 use rt::vec3::{Color, Point3};
+use rt::Float;
 use rt::{HitList, Sphere};
 
 pub fn simple_scene() -> HitList {
@@ -68,9 +69,9 @@ pub fn random_scene() -> HitList {
         for b in -11..11 {
             let choose_mat = random_double();
             let center = Point3::new(
-                a as f64 + 0.9 * random_double(),
+                a as Float + 0.9 * random_double(),
                 0.2,
-                b as f64 + 0.9 * random_double(),
+                b as Float + 0.9 * random_double(),
             );
 
             if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
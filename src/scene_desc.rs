@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::background::SolidBackground;
+use crate::material::{Dialectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::render::Renderer;
+use crate::vec3::Vec3;
+use crate::Float;
+use crate::{Camera, HitList, Sphere};
+
+// A scene as it appears in a JSON description. Vectors and colors are `[x, y, z]` arrays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneDesc {
+    // materials by name, referenced from the objects
+    #[serde(default)]
+    pub materials: HashMap<String, MaterialDesc>,
+    #[serde(default)]
+    pub spheres: Vec<SphereDesc>,
+    pub camera: CameraDesc,
+    pub render: RenderDesc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MaterialDesc {
+    Lambertian { albedo: [Float; 3] },
+    Metal { albedo: [Float; 3], fuzz: Float },
+    Dielectric { index_of_refraction: Float },
+    Light { emit: [Float; 3] },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SphereDesc {
+    pub center: [Float; 3],
+    pub radius: Float,
+    pub material: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraDesc {
+    pub lookfrom: [Float; 3],
+    pub lookat: [Float; 3],
+    #[serde(default = "default_vup")]
+    pub vup: [Float; 3],
+    pub vfov: Float,
+    #[serde(default)]
+    pub aperture: Float,
+    #[serde(default = "default_focus_dist")]
+    pub focus_dist: Float,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderDesc {
+    pub image_width: u32,
+    pub image_height: u32,
+    pub samples_per_pixel: Option<u32>,
+    pub max_depth: Option<i32>,
+    pub background: Option<[Float; 3]>,
+    pub seed: Option<u64>,
+}
+
+fn default_vup() -> [Float; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_focus_dist() -> Float {
+    1.0
+}
+
+fn vec3([x, y, z]: [Float; 3]) -> Vec3 {
+    Vec3::new(x, y, z)
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    Json(serde_json::Error),
+    // an object refers to a material name that isn't defined
+    UnknownMaterial(String),
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Json(err) => write!(f, "invalid scene description: {err}"),
+            SceneError::UnknownMaterial(name) => write!(f, "unknown material: {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(err: serde_json::Error) -> Self {
+        SceneError::Json(err)
+    }
+}
+
+impl MaterialDesc {
+    fn build(&self) -> Arc<dyn Material> {
+        match *self {
+            MaterialDesc::Lambertian { albedo } => Arc::new(Lambertian::new(vec3(albedo))),
+            MaterialDesc::Metal { albedo, fuzz } => Arc::new(Metal::new(vec3(albedo), fuzz)),
+            MaterialDesc::Dielectric {
+                index_of_refraction,
+            } => Arc::new(Dialectric::new(index_of_refraction)),
+            MaterialDesc::Light { emit } => Arc::new(DiffuseLight::new(vec3(emit))),
+        }
+    }
+}
+
+impl SceneDesc {
+    // Builds the world, camera and renderer this description asks for.
+    pub fn build(&self) -> Result<(HitList, Camera, Renderer), SceneError> {
+        let materials: HashMap<&str, Arc<dyn Material>> = self
+            .materials
+            .iter()
+            .map(|(name, desc)| (name.as_str(), desc.build()))
+            .collect();
+
+        let mut world = HitList::new();
+        for sphere in &self.spheres {
+            let mat_ptr = materials
+                .get(sphere.material.as_str())
+                .ok_or_else(|| SceneError::UnknownMaterial(sphere.material.clone()))?;
+            world.add(Box::new(Sphere::new(
+                vec3(sphere.center),
+                sphere.radius,
+                mat_ptr.clone(),
+            )));
+        }
+
+        let r = &self.render;
+        let defaults = Renderer::new(r.image_width, r.image_height);
+        let renderer = Renderer {
+            samples_per_pixel: r.samples_per_pixel.unwrap_or(defaults.samples_per_pixel),
+            max_depth: r.max_depth.unwrap_or(defaults.max_depth),
+            background: match r.background {
+                Some(color) => Arc::new(SolidBackground(vec3(color))),
+                None => defaults.background,
+            },
+            seed: r.seed.unwrap_or(defaults.seed),
+            ..defaults
+        };
+
+        let c = &self.camera;
+        let camera = Camera::new(
+            vec3(c.lookfrom),
+            vec3(c.lookat),
+            vec3(c.vup),
+            c.vfov,
+            r.image_width as Float / r.image_height as Float,
+            c.aperture,
+            c.focus_dist,
+        );
+
+        Ok((world, camera, renderer))
+    }
+}
+
+// Loads scenes from JSON descriptions.
+pub struct Scene;
+
+impl Scene {
+    pub fn from_json(json: &str) -> Result<(HitList, Camera, Renderer), SceneError> {
+        let desc: SceneDesc = serde_json::from_str(json)?;
+        desc.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::{Color, Point3};
+    use crate::Ray;
+
+    const TWO_SPHERES: &str = r#"{
+        "materials": {
+            "ground": { "type": "lambertian", "albedo": [0.8, 0.8, 0.0] },
+            "glass": { "type": "dielectric", "index_of_refraction": 1.5 }
+        },
+        "spheres": [
+            { "center": [0, -100.5, -1], "radius": 100, "material": "ground" },
+            { "center": [0, 0, -1], "radius": 0.5, "material": "glass" }
+        ],
+        "camera": { "lookfrom": [3, 2, 1], "lookat": [0, 0, -1], "vfov": 40 },
+        "render": { "image_width": 40, "image_height": 20, "samples_per_pixel": 8 }
+    }"#;
+
+    #[test]
+    fn test_from_json() {
+        let (world, camera, renderer) = Scene::from_json(TWO_SPHERES).unwrap();
+        assert_eq!(world.objects.len(), 2);
+        assert_eq!(
+            camera.get_ray(0.5, 0.5).origin(),
+            Point3::new(3.0, 2.0, 1.0)
+        );
+        assert_eq!(renderer.image_width, 40);
+        assert_eq!(renderer.samples_per_pixel, 8);
+        assert_eq!(renderer.max_depth, 50);
+        let up = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(renderer.background.color(&up), Color::new(0.5, 0.7, 1.0));
+    }
+
+    #[test]
+    fn test_unknown_material() {
+        let json = TWO_SPHERES.replace(r#""material": "glass""#, r#""material": "gold""#);
+        match Scene::from_json(&json) {
+            Err(SceneError::UnknownMaterial(name)) => assert_eq!(name, "gold"),
+            other => panic!("expected an unknown material error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        assert!(matches!(
+            Scene::from_json("{ \"spheres\": "),
+            Err(SceneError::Json(_))
+        ));
+    }
+}
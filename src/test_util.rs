@@ -0,0 +1,34 @@
+// Fixtures and assertions shared by the unit tests.
+
+use std::sync::Arc;
+
+use crate::material::{Lambertian, Material};
+use crate::vec3::{Point3, Vec3, COLOR_WHITE};
+use crate::{Float, Hittable, Sphere};
+
+// A test tolerance picked for `f64`, widened to what `f32` can resolve in f32 mode.
+pub(crate) fn tolerance(f64_tolerance: Float) -> Float {
+    if cfg!(feature = "f32") {
+        f64_tolerance.max(1e-4)
+    } else {
+        f64_tolerance
+    }
+}
+
+pub(crate) fn assert_vec_near(actual: Vec3, expected: Vec3) {
+    assert!(
+        (actual - expected).length() < tolerance(1e-9),
+        "expected {:?}, got {:?}",
+        expected,
+        actual
+    );
+}
+
+// A plain white diffuse material, for tests that don't care about shading.
+pub(crate) fn mat() -> Arc<dyn Material> {
+    Arc::new(Lambertian::new(COLOR_WHITE))
+}
+
+pub(crate) fn sphere(center: Point3, radius: Float) -> Box<dyn Hittable> {
+    Box::new(Sphere::new(center, radius, mat()))
+}
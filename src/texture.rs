@@ -0,0 +1,660 @@
+#[cfg(feature = "png")]
+use std::path::Path;
+use std::sync::Arc;
+
+#[cfg(feature = "png")]
+use image::{ImageError, RgbImage};
+
+use crate::consts::PI;
+use crate::perlin::Perlin;
+use crate::util::mix_seed;
+use crate::vec3::{dot, Color, Point3, Vec3, COLOR_WHITE};
+use crate::Float;
+
+pub trait Texture: Send + Sync {
+    fn value(&self, u: Float, v: Float, p: Point3) -> Color;
+}
+
+pub struct SolidColor {
+    color_value: Color,
+}
+
+impl SolidColor {
+    pub fn new(color_value: Color) -> Self {
+        Self { color_value }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: Float, _v: Float, _p: Point3) -> Color {
+        self.color_value
+    }
+}
+
+// A 3D checker pattern, alternating between two textures every `PI / scale` units.
+pub struct CheckerTexture {
+    scale: Float,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl CheckerTexture {
+    pub fn new(scale: Float, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Self { scale, even, odd }
+    }
+
+    pub fn from_colors(scale: Float, even: Color, odd: Color) -> Self {
+        Self::new(
+            scale,
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: Float, v: Float, p: Point3) -> Color {
+        let sines =
+            (self.scale * p.x()).sin() * (self.scale * p.y()).sin() * (self.scale * p.z()).sin();
+        if sines < 0.0 {
+            self.odd.value(u, v, p)
+        } else {
+            self.even.value(u, v, p)
+        }
+    }
+}
+
+// A checker pattern in texture space, `tiles_u` squares across and `tiles_v` up, so it
+// lines up with the edges of flat primitives like quads and triangles.
+pub struct UvChecker {
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+    tiles_u: u32,
+    tiles_v: u32,
+}
+
+impl UvChecker {
+    pub fn new(even: Arc<dyn Texture>, odd: Arc<dyn Texture>, tiles_u: u32, tiles_v: u32) -> Self {
+        Self {
+            even,
+            odd,
+            tiles_u,
+            tiles_v,
+        }
+    }
+
+    pub fn from_colors(even: Color, odd: Color, tiles_u: u32, tiles_v: u32) -> Self {
+        Self::new(
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+            tiles_u,
+            tiles_v,
+        )
+    }
+}
+
+impl Texture for UvChecker {
+    fn value(&self, u: Float, v: Float, p: Point3) -> Color {
+        let column = (u * self.tiles_u as Float).floor() as i64;
+        let row = (v * self.tiles_v as Float).floor() as i64;
+        if (column + row).rem_euclid(2) == 0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+}
+
+// A marble-like pattern: sine stripes along z, phase-shifted by turbulence.
+pub struct NoiseTexture {
+    noise: Perlin,
+    scale: Float,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: Float) -> Self {
+        Self {
+            noise: Perlin::new(),
+            scale,
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: Float, _v: Float, p: Point3) -> Color {
+        COLOR_WHITE
+            * 0.5
+            * (1.0 + Float::sin(self.scale * p.z() + 10.0 * self.noise.turbulence(p, 7)))
+    }
+}
+
+// Veined stone: sine bands along z between `base` and `vein`, bent out of line by
+// `turbulence` times `octaves` of noise.
+pub struct MarbleTexture {
+    noise: Perlin,
+    base: Color,
+    vein: Color,
+    scale: Float,
+    turbulence: Float,
+    octaves: u32,
+}
+
+impl MarbleTexture {
+    pub fn new(base: Color, vein: Color, scale: Float, turbulence: Float, octaves: u32) -> Self {
+        Self {
+            noise: Perlin::new(),
+            base,
+            vein,
+            scale,
+            turbulence,
+            octaves,
+        }
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: Float, _v: Float, p: Point3) -> Color {
+        let phase = self.scale * p.z() + self.turbulence * self.noise.turbulence(p, self.octaves);
+        // veins are the thin peaks of the wave
+        let t = (0.5 * (1.0 + phase.sin())).powi(4);
+        (1.0 - t) * self.base + t * self.vein
+    }
+}
+
+// Wood grain: growth rings around the y axis, `ring_spacing` apart, alternating from
+// `color_a` to `color_b` and warped by `turbulence` times `octaves` of noise.
+pub struct WoodTexture {
+    noise: Perlin,
+    ring_spacing: Float,
+    color_a: Color,
+    color_b: Color,
+    turbulence: Float,
+    octaves: u32,
+}
+
+impl WoodTexture {
+    pub fn new(
+        ring_spacing: Float,
+        color_a: Color,
+        color_b: Color,
+        turbulence: Float,
+        octaves: u32,
+    ) -> Self {
+        Self {
+            noise: Perlin::new(),
+            ring_spacing,
+            color_a,
+            color_b,
+            turbulence,
+            octaves,
+        }
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: Float, _v: Float, p: Point3) -> Color {
+        let radius = Float::sqrt(p.x() * p.x() + p.z() * p.z());
+        let rings =
+            radius / self.ring_spacing + self.turbulence * self.noise.turbulence(p, self.octaves);
+        let t = 0.5 * (1.0 + (2.0 * PI * rings).sin());
+        (1.0 - t) * self.color_a + t * self.color_b
+    }
+}
+
+// Tangent-space normals from the slopes of a height field, encoded as colors the way
+// normal map images store them: 0.5 * (normal + 1), so flat ground is (0.5, 0.5, 1).
+pub struct NormalMap {
+    // height is the average of the three channels
+    height: Arc<dyn Texture>,
+    // how steep a unit change in height per unit of u (or v) is
+    strength: Float,
+}
+
+impl NormalMap {
+    pub fn new(height: Arc<dyn Texture>, strength: Float) -> Self {
+        Self { height, strength }
+    }
+
+    fn height_at(&self, u: Float, v: Float, p: Point3) -> Float {
+        let c = self.height.value(u, v, p);
+        (c.x() + c.y() + c.z()) / 3.0
+    }
+}
+
+impl Texture for NormalMap {
+    fn value(&self, u: Float, v: Float, p: Point3) -> Color {
+        // central differences in texture space
+        let h = 1e-3;
+        let du = (self.height_at(u + h, v, p) - self.height_at(u - h, v, p)) / (2.0 * h);
+        let dv = (self.height_at(u, v + h, p) - self.height_at(u, v - h, p)) / (2.0 * h);
+        let normal = Vec3::new(-self.strength * du, -self.strength * dv, 1.0).unit_vector();
+        0.5 * (normal + COLOR_WHITE)
+    }
+}
+
+// The direction a `GradientTexture` runs along.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GradientAxis {
+    U,
+    V,
+    // through space, from the first point to the second
+    World(Point3, Point3),
+}
+
+// A linear blend from `a` to `b`, held constant past either end.
+pub struct GradientTexture {
+    a: Color,
+    b: Color,
+    axis: GradientAxis,
+}
+
+impl GradientTexture {
+    pub fn new(a: Color, b: Color, axis: GradientAxis) -> Self {
+        Self { a, b, axis }
+    }
+}
+
+impl Texture for GradientTexture {
+    fn value(&self, u: Float, v: Float, p: Point3) -> Color {
+        let t = match self.axis {
+            GradientAxis::U => u,
+            GradientAxis::V => v,
+            GradientAxis::World(from, to) => {
+                let along = to - from;
+                dot(p - from, along) / along.length_squared()
+            }
+        };
+        let t = t.clamp(0.0, 1.0);
+        (1.0 - t) * self.a + t * self.b
+    }
+}
+
+// Polka dots: a `dot` colored disk of `radius` centered in each `spacing` sized square
+// of the (u, v) grid, on a `background` color.
+pub struct DotsTexture {
+    background: Color,
+    dot: Color,
+    radius: Float,
+    spacing: Float,
+}
+
+impl DotsTexture {
+    pub fn new(background: Color, dot: Color, radius: Float, spacing: Float) -> Self {
+        Self {
+            background,
+            dot,
+            radius,
+            spacing,
+        }
+    }
+}
+
+impl Texture for DotsTexture {
+    fn value(&self, u: Float, v: Float, _p: Point3) -> Color {
+        // offset from the center of this grid square
+        let offset =
+            |x: Float| (x / self.spacing).rem_euclid(1.0) * self.spacing - self.spacing / 2.0;
+        let (du, dv) = (offset(u), offset(v));
+        if du * du + dv * dv < self.radius * self.radius {
+            self.dot
+        } else {
+            self.background
+        }
+    }
+}
+
+// Which distance a `WorleyTexture` shows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorleyFeature {
+    // to the nearest feature point: round cells, darkest at their centers
+    F1,
+    // to the second nearest: bright ridges where neighboring cells meet
+    F2,
+}
+
+// Cellular (Voronoi) noise: one feature point scattered in each unit cell of the grid
+// scaled by `scale`, and the distance to the nearest ones as a gray level.
+pub struct WorleyTexture {
+    scale: Float,
+    feature: WorleyFeature,
+}
+
+impl WorleyTexture {
+    pub fn new(scale: Float, feature: WorleyFeature) -> Self {
+        Self { scale, feature }
+    }
+
+    // The feature point of cell (i, j, k), in scaled space. Hashed from the cell, so the
+    // pattern is the same in every run.
+    fn feature_point(i: i64, j: i64, k: i64) -> Point3 {
+        let hash = [i, j, k]
+            .iter()
+            .fold(0, |hash, &n| mix_seed(hash, n as u64));
+        let offset = |shift: u32| ((hash >> shift) & 0x1F_FFFF) as Float / 0x20_0000 as Float;
+        Point3::new(
+            i as Float + offset(0),
+            j as Float + offset(21),
+            k as Float + offset(42),
+        )
+    }
+
+    // The nearest and second nearest feature point distances from `p`, in scaled space.
+    fn distances(&self, p: Point3) -> (Float, Float) {
+        let p = self.scale * p;
+        let (i, j, k) = (
+            p.x().floor() as i64,
+            p.y().floor() as i64,
+            p.z().floor() as i64,
+        );
+        let mut nearest = (Float::INFINITY, Float::INFINITY);
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                for dk in -1..=1 {
+                    let d = (Self::feature_point(i + di, j + dj, k + dk) - p).length();
+                    if d < nearest.0 {
+                        nearest = (d, nearest.0);
+                    } else if d < nearest.1 {
+                        nearest.1 = d;
+                    }
+                }
+            }
+        }
+        nearest
+    }
+}
+
+impl Texture for WorleyTexture {
+    fn value(&self, _u: Float, _v: Float, p: Point3) -> Color {
+        let (f1, f2) = self.distances(p);
+        match self.feature {
+            WorleyFeature::F1 => f1 * COLOR_WHITE,
+            WorleyFeature::F2 => f2 * COLOR_WHITE,
+        }
+    }
+}
+
+// Maps an image (PNG or JPEG) onto a surface using its (u, v) coordinates.
+#[cfg(feature = "png")]
+pub struct ImageTexture {
+    image: RgbImage,
+}
+
+#[cfg(feature = "png")]
+impl ImageTexture {
+    pub fn new(path: &Path) -> Result<Self, ImageError> {
+        Ok(Self::from_image(image::open(path)?.to_rgb8()))
+    }
+
+    pub fn from_image(image: RgbImage) -> Self {
+        Self { image }
+    }
+}
+
+#[cfg(feature = "png")]
+impl Texture for ImageTexture {
+    fn value(&self, u: Float, v: Float, _p: Point3) -> Color {
+        let (width, height) = self.image.dimensions();
+        // If we have no texture data, return magenta as a debugging aid.
+        if width == 0 || height == 0 || !u.is_finite() || !v.is_finite() {
+            return Color::new(1.0, 0.0, 1.0);
+        }
+
+        // Clamp input texture coordinates to [0,1] x [1,0]; images store rows top to bottom.
+        let u = u.clamp(0.0, 1.0);
+        let v = 1.0 - v.clamp(0.0, 1.0);
+
+        let i = ((u * width as Float) as u32).min(width - 1);
+        let j = ((v * height as Float) as u32).min(height - 1);
+
+        let color_scale = 1.0 / 255.0;
+        let [r, g, b] = self.image.get_pixel(i, j).0;
+        Color::new(
+            color_scale * r as Float,
+            color_scale * g as Float,
+            color_scale * b as Float,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::quad::Quad;
+    use crate::tolerance;
+    use crate::{Hittable, Ray, INFINITY};
+
+    #[test]
+    fn test_checker_alternates_between_cells() {
+        let even = Color::new(1.0, 0.0, 0.0);
+        let odd = Color::new(0.0, 0.0, 1.0);
+        // scale PI => one cell per unit
+        let checker = CheckerTexture::from_colors(PI, even, odd);
+
+        assert_eq!(checker.value(0.0, 0.0, Point3::new(0.5, 0.5, 0.5)), even);
+        assert_eq!(checker.value(0.0, 0.0, Point3::new(1.5, 0.5, 0.5)), odd);
+        assert_eq!(checker.value(0.0, 0.0, Point3::new(1.5, 1.5, 0.5)), even);
+        assert_eq!(checker.value(0.0, 0.0, Point3::new(0.5, 0.5, -0.5)), odd);
+    }
+
+    #[test]
+    fn test_uv_checker_tiles_a_quad() {
+        let even = Color::new(1.0, 1.0, 1.0);
+        let odd = Color::new(0.0, 0.0, 0.0);
+        // a 4 by 2 quad, with its texture coordinates running along its edges
+        let quad = Quad::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let color_at = |checker: &UvChecker, x: Float, y: Float| {
+            let r = Ray::new(Point3::new(x, y, 1.0), Vec3::new(0.0, 0.0, -1.0));
+            let rec = quad.hit(&r, 0.001, INFINITY).unwrap();
+            checker.value(rec.u, rec.v, rec.p)
+        };
+
+        // squares one unit on a side
+        let checker = UvChecker::from_colors(even, odd, 4, 2);
+        for (x, y, expected) in [
+            (0.5, 0.5, even),
+            (1.5, 0.5, odd),
+            (2.5, 0.5, even),
+            (0.5, 1.5, odd),
+            (3.5, 1.5, even),
+        ] {
+            assert_eq!(color_at(&checker, x, y), expected, "at ({x}, {y})");
+        }
+
+        // twice the tiles, half the size
+        let fine = UvChecker::from_colors(even, odd, 8, 4);
+        assert_eq!(color_at(&fine, 0.25, 0.25), even);
+        assert_eq!(color_at(&fine, 0.75, 0.25), odd);
+        assert_eq!(color_at(&fine, 0.25, 0.75), odd);
+        assert_eq!(color_at(&fine, 0.75, 0.75), even);
+    }
+
+    #[test]
+    fn test_noise_texture_is_deterministic_and_in_range() {
+        let texture = NoiseTexture::new(4.0);
+        for i in 0..50 {
+            let p = Point3::new(i as Float * 0.13, i as Float * -0.29, i as Float * 0.07);
+            let c = texture.value(0.0, 0.0, p);
+            assert_eq!(c, texture.value(0.0, 0.0, p));
+            assert!((0.0..=1.0).contains(&c.x()));
+            assert_eq!(c.x(), c.y());
+        }
+    }
+
+    #[test]
+    fn test_gradient_runs_between_endpoints() {
+        let a = Color::new(1.0, 0.0, 0.0);
+        let b = Color::new(0.0, 0.0, 1.0);
+        let across_u = GradientTexture::new(a, b, GradientAxis::U);
+        let origin = Point3::new(0.0, 0.0, 0.0);
+        assert_eq!(across_u.value(0.0, 0.7, origin), a);
+        assert_eq!(across_u.value(1.0, 0.7, origin), b);
+        assert_eq!(across_u.value(0.5, 0.7, origin), 0.5 * (a + b));
+
+        let upward = GradientTexture::new(
+            a,
+            b,
+            GradientAxis::World(Point3::new(0.0, -1.0, 0.0), Point3::new(0.0, 3.0, 0.0)),
+        );
+        assert_eq!(upward.value(0.0, 0.0, Point3::new(5.0, -1.0, 2.0)), a);
+        assert_eq!(upward.value(0.0, 0.0, Point3::new(5.0, 3.0, 2.0)), b);
+        assert_eq!(upward.value(0.0, 0.0, Point3::new(0.0, 10.0, 0.0)), b);
+        assert_eq!(
+            upward.value(0.0, 0.0, Point3::new(0.0, 1.0, 0.0)),
+            0.5 * (a + b)
+        );
+    }
+
+    #[test]
+    fn test_dots_cover_grid_centers() {
+        let background = Color::new(1.0, 1.0, 1.0);
+        let dot = Color::new(1.0, 0.0, 0.0);
+        let dots = DotsTexture::new(background, dot, 0.05, 0.25);
+        let p = Point3::new(0.0, 0.0, 0.0);
+        // grid squares are centered on odd multiples of 0.125
+        assert_eq!(dots.value(0.125, 0.375, p), dot);
+        assert_eq!(dots.value(0.16, 0.85, p), dot);
+        assert_eq!(dots.value(0.2, 0.375, p), background);
+        assert_eq!(dots.value(0.25, 0.25, p), background);
+    }
+
+    #[test]
+    fn test_worley_distances() {
+        let scale = 3.0;
+        let f1 = WorleyTexture::new(scale, WorleyFeature::F1);
+        let f2 = WorleyTexture::new(scale, WorleyFeature::F2);
+        for (i, j, k) in [(0, 0, 0), (2, -1, 5), (-7, 3, -2)] {
+            let feature = WorleyTexture::feature_point(i, j, k) / scale;
+            assert!(f1.value(0.0, 0.0, feature).x() < tolerance(1e-9));
+        }
+        for n in 0..200 {
+            let n = n as Float;
+            let p = Point3::new(n * 0.137, n * -0.071, n * 0.029);
+            let near = f1.value(0.0, 0.0, p);
+            assert_eq!(near, f1.value(0.0, 0.0, p));
+            assert!(f2.value(0.0, 0.0, p).x() > near.x());
+        }
+    }
+
+    // Total color change between neighboring points across a small patch, spanned by
+    // the steps `du` and `dv` from `corner`.
+    fn local_variation(texture: &dyn Texture, corner: Point3, du: Vec3, dv: Vec3) -> Float {
+        let at =
+            |i: u32, j: u32| texture.value(0.0, 0.0, corner + i as Float * du + j as Float * dv);
+        let mut variation = 0.0;
+        for i in 0..20 {
+            for j in 0..20 {
+                variation +=
+                    (at(i + 1, j) - at(i, j)).length() + (at(i, j + 1) - at(i, j)).length();
+            }
+        }
+        variation
+    }
+
+    #[test]
+    fn test_marble_and_wood_are_deterministic_and_warp_with_turbulence() {
+        let (white, gray) = (Color::new(0.9, 0.9, 0.9), Color::new(0.2, 0.2, 0.25));
+        let (light, dark) = (Color::new(0.8, 0.6, 0.3), Color::new(0.4, 0.25, 0.1));
+        let p = Point3::new(0.4, -1.3, 2.2);
+        // the same noise for each strength, so only the strength differs
+        let marble = |turbulence| {
+            crate::util::seed_rng(7);
+            MarbleTexture::new(white, gray, 4.0, turbulence, 7)
+        };
+        let wood = |turbulence| {
+            crate::util::seed_rng(7);
+            WoodTexture::new(0.1, light, dark, turbulence, 7)
+        };
+
+        // two textures built from the same seed agree
+        assert_eq!(
+            marble(1.0).value(0.0, 0.0, p),
+            marble(1.0).value(0.0, 0.0, p)
+        );
+        assert_eq!(wood(0.5).value(0.0, 0.0, p), wood(0.5).value(0.0, 0.0, p));
+
+        // variation grows with the turbulence, across a patch where the bands or rings
+        // alone would barely change
+        let assert_warps = |textures: [Box<dyn Texture>; 3], corner: Point3| {
+            let (du, dv) = (Vec3::new(0.02, 0.0, 0.0), Vec3::new(0.0, 0.02, 0.0));
+            let variation = textures.map(|t| local_variation(t.as_ref(), corner, du, dv));
+            assert!(
+                variation[0] < variation[1] && variation[1] < variation[2],
+                "{variation:?}"
+            );
+        };
+        assert_warps(
+            [
+                Box::new(marble(0.0)),
+                Box::new(marble(1.0)),
+                Box::new(marble(8.0)),
+            ],
+            Point3::new(0.3, 1.1, 0.7),
+        );
+        // x runs around the rings here
+        assert_warps(
+            [
+                Box::new(wood(0.0)),
+                Box::new(wood(0.5)),
+                Box::new(wood(2.0)),
+            ],
+            Point3::new(0.0, 1.1, 2.0),
+        );
+    }
+
+    #[test]
+    fn test_normal_map_follows_height_slope() {
+        let p = Point3::new(0.0, 0.0, 0.0);
+        let flat = NormalMap::new(Arc::new(SolidColor::new(COLOR_WHITE)), 1.0);
+        assert_eq!(flat.value(0.3, 0.6, p), Color::new(0.5, 0.5, 1.0));
+
+        // height rising with u: the surface faces back toward -u
+        struct Ramp;
+        impl Texture for Ramp {
+            fn value(&self, u: Float, _v: Float, _p: Point3) -> Color {
+                Color::new(u, u, u)
+            }
+        }
+        let sloped = NormalMap::new(Arc::new(Ramp), 1.0).value(0.5, 0.5, p);
+        let expected = 0.5 * (Vec3::new(-1.0, 0.0, 1.0).unit_vector() + COLOR_WHITE);
+        assert!((sloped - expected).length() < 1e-3, "{sloped:?}");
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_image_texture_samples_corners() {
+        let img = RgbImage::from_fn(2, 2, |x, y| match (x, y) {
+            (0, 0) => image::Rgb([255, 0, 0]),
+            (1, 0) => image::Rgb([0, 255, 0]),
+            (0, 1) => image::Rgb([0, 0, 255]),
+            _ => image::Rgb([255, 255, 255]),
+        });
+        let path = std::env::temp_dir().join("rt_test_image_texture.png");
+        img.save(&path).unwrap();
+        let texture = ImageTexture::new(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let p = Point3::new(0.0, 0.0, 0.0);
+        // v runs bottom to top
+        assert_eq!(texture.value(0.0, 1.0, p), Color::new(1.0, 0.0, 0.0));
+        assert_eq!(texture.value(1.0, 1.0, p), Color::new(0.0, 1.0, 0.0));
+        assert_eq!(texture.value(0.0, 0.0, p), Color::new(0.0, 0.0, 1.0));
+        assert_eq!(texture.value(1.0, 0.0, p), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(texture.value(0.25, 0.75, p), Color::new(1.0, 0.0, 0.0));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_empty_image_texture_is_magenta() {
+        let texture = ImageTexture::from_image(RgbImage::new(0, 0));
+        assert_eq!(
+            texture.value(0.5, 0.5, Point3::new(0.0, 0.0, 0.0)),
+            Color::new(1.0, 0.0, 1.0)
+        );
+    }
+}
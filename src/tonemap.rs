@@ -0,0 +1,63 @@
+use crate::vec3::Color;
+use crate::Float;
+
+// Maps high dynamic range colors into [0, 1) before gamma correction.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ToneMap {
+    // just clamp, so bright lights blow out to white
+    #[default]
+    None,
+    Reinhard,
+    AcesFilmic,
+}
+
+impl ToneMap {
+    pub fn apply(self, c: Color) -> Color {
+        match self {
+            ToneMap::None => c,
+            ToneMap::Reinhard => reinhard(c),
+            ToneMap::AcesFilmic => aces_filmic(c),
+        }
+    }
+}
+
+pub fn reinhard(c: Color) -> Color {
+    c.map(|x| x / (1.0 + x))
+}
+
+// Krzysztof Narkowicz's fit of the ACES filmic curve.
+pub fn aces_filmic(c: Color) -> Color {
+    const A: Float = 2.51;
+    const B: Float = 0.03;
+    const C: Float = 2.43;
+    const D: Float = 0.59;
+    const E: Float = 0.14;
+    c.map(|x| ((x * (A * x + B)) / (x * (C * x + D) + E)).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec3::COLOR_BLACK;
+
+    #[test]
+    fn test_reinhard() {
+        let c = reinhard(Color::new(4.0, 4.0, 4.0));
+        assert_eq!(c, Color::new(0.8, 0.8, 0.8));
+        assert!(c.x() < 1.0);
+        assert_eq!(reinhard(COLOR_BLACK), COLOR_BLACK);
+    }
+
+    #[test]
+    fn test_aces_filmic() {
+        let c = aces_filmic(Color::new(4.0, 4.0, 4.0));
+        assert!(c.x() > 0.8 && c.x() <= 1.0);
+        assert!(aces_filmic(COLOR_BLACK).length() < 1e-12);
+    }
+
+    #[test]
+    fn test_none_is_identity() {
+        let c = Color::new(4.0, 0.5, 0.0);
+        assert_eq!(ToneMap::None.apply(c), c);
+    }
+}
@@ -0,0 +1,240 @@
+use crate::consts::PI;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::onb::Onb;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+// A ring around `axis`: every point `minor_radius` away from the circle of `major_radius`.
+pub struct Torus {
+    pub center: Point3,
+    pub axis: Vec3,
+    pub major_radius: Float,
+    pub minor_radius: Float,
+    pub mat_ptr: Arc<dyn Material>,
+    // torus space has the axis along y
+    onb: Onb,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        major_radius: Float,
+        minor_radius: Float,
+        mat_ptr: Arc<dyn Material>,
+    ) -> Self {
+        Torus {
+            center,
+            axis: axis.unit_vector(),
+            major_radius,
+            minor_radius,
+            mat_ptr,
+            onb: Onb::build_from_w(axis),
+        }
+    }
+
+    // world -> torus space, as (x, y, z) with y along the axis
+    fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(dot(v, self.onb.u), dot(v, self.onb.w), dot(v, self.onb.v))
+    }
+
+    fn to_world(&self, v: Vec3) -> Vec3 {
+        self.onb.local(v.x(), v.z(), v.y())
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let o = self.to_local(r.orig - self.center);
+        let d = self.to_local(r.dir);
+        let big_r2 = self.major_radius * self.major_radius;
+        let small_r2 = self.minor_radius * self.minor_radius;
+
+        // (|p|^2 + R^2 - r^2)^2 - 4 R^2 (px^2 + pz^2) = 0, with p = o + t d
+        let m = dot(d, d);
+        let n = dot(o, d);
+        let k = dot(o, o) + big_r2 - small_r2;
+        let coeffs = [
+            k * k - 4.0 * big_r2 * (o.x() * o.x() + o.z() * o.z()),
+            4.0 * n * k - 8.0 * big_r2 * (o.x() * d.x() + o.z() * d.z()),
+            4.0 * n * n + 2.0 * m * k - 4.0 * big_r2 * (d.x() * d.x() + d.z() * d.z()),
+            4.0 * m * n,
+            m * m,
+        ];
+
+        // Only look for roots while the ray is inside the torus' bounding sphere.
+        let bound = self.major_radius + self.minor_radius;
+        let half_b = n;
+        let c = dot(o, o) - bound * bound;
+        let discriminant = half_b * half_b - m * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let sqrtd = discriminant.sqrt();
+        let lo = Float::max(t_min, (-half_b - sqrtd) / m);
+        let hi = Float::min(t_max, (-half_b + sqrtd) / m);
+        if lo > hi {
+            return None;
+        }
+        let t = *real_roots(&coeffs, lo, hi).first()?;
+
+        let p = o + t * d;
+        // gradient of the implicit function
+        let s = dot(p, p) + big_r2 - small_r2;
+        let local_normal = Vec3::new(
+            4.0 * p.x() * s - 8.0 * big_r2 * p.x(),
+            4.0 * p.y() * s,
+            4.0 * p.z() * s - 8.0 * big_r2 * p.z(),
+        );
+        let outward_normal = self.to_world(local_normal).unit_vector();
+
+        // u: angle around the axis; v: angle around the tube
+        let ring_angle = Float::atan2(p.z(), p.x());
+        let ring_distance = Float::sqrt(p.x() * p.x() + p.z() * p.z()) - self.major_radius;
+        let tube_angle = Float::atan2(p.y(), ring_distance);
+
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u: (ring_angle + PI) / (2.0 * PI),
+            v: (tube_angle + PI) / (2.0 * PI),
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // The ring extends major_radius * sin(angle to the axis) along each world axis.
+        let ring = (Vec3::new(1.0, 1.0, 1.0) - self.axis * self.axis)
+            .map(|e| self.major_radius * e.max(0.0).sqrt());
+        let extent = ring.map(|e| e + self.minor_radius);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+fn evaluate(coeffs: &[Float], x: Float) -> Float {
+    coeffs.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+}
+
+// Real roots in [lo, hi] of the polynomial with `coeffs` (constant term first), ascending.
+// Finds the derivative's roots recursively; the polynomial is monotone between them,
+// so each of those intervals holds at most one root, found by bisection.
+fn real_roots(coeffs: &[Float], lo: Float, hi: Float) -> Vec<Float> {
+    let degree = coeffs.len() - 1;
+    if degree == 1 {
+        let root = -coeffs[0] / coeffs[1];
+        return if (lo..=hi).contains(&root) {
+            vec![root]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let derivative: Vec<Float> = (1..=degree).map(|i| i as Float * coeffs[i]).collect();
+    let mut bounds = vec![lo];
+    bounds.extend(real_roots(&derivative, lo, hi));
+    bounds.push(hi);
+
+    let mut roots = Vec::new();
+    for pair in bounds.windows(2) {
+        let (mut a, mut b) = (pair[0], pair[1]);
+        let (fa, fb) = (evaluate(coeffs, a), evaluate(coeffs, b));
+        if fa == 0.0 {
+            roots.push(a);
+            continue;
+        }
+        if fa.signum() == fb.signum() {
+            continue;
+        }
+        for _ in 0..100 {
+            let mid = 0.5 * (a + b);
+            if evaluate(coeffs, mid).signum() == fa.signum() {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        roots.push(0.5 * (a + b));
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::tolerance;
+    use crate::vec3::COLOR_WHITE;
+
+    // lying flat around the y axis, from radius 0.75 to 1.25
+    fn torus() -> Torus {
+        Torus::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            1.0,
+            0.25,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_ray_through_hole_misses() {
+        let r = Ray::new(Point3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(torus().hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_hit_outer_ring() {
+        let r = Ray::new(Point3::new(5.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        let rec = torus().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 3.75).abs() < tolerance(1e-9));
+        assert!((rec.normal - Vec3::new(1.0, 0.0, 0.0)).length() < tolerance(1e-9));
+        assert!(rec.front_face);
+
+        // from above, onto the top of the tube
+        let r = Ray::new(Point3::new(0.0, 5.0, -1.0), Vec3::new(0.0, -2.0, 0.0));
+        let rec = torus().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.p - Point3::new(0.0, 0.25, -1.0)).length() < tolerance(1e-9));
+        assert!((rec.normal - Vec3::new(0.0, 1.0, 0.0)).length() < tolerance(1e-9));
+    }
+
+    #[test]
+    fn test_hit_inner_ring() {
+        // from the middle of the hole, outward
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let rec = torus().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 0.75).abs() < tolerance(1e-9));
+        assert!((rec.normal - Vec3::new(0.0, 0.0, -1.0)).length() < tolerance(1e-9));
+        assert!(rec.front_face);
+    }
+
+    #[test]
+    fn test_tilted_torus() {
+        // around the x axis, so the hole faces along x
+        let torus = Torus::new(
+            Point3::new(0.0, 0.0, -3.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            1.0,
+            0.25,
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        );
+        let through_hole = Ray::new(Point3::new(5.0, 0.0, -3.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert!(torus.hit(&through_hole, 0.001, Float::INFINITY).is_none());
+
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = torus.hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert!((rec.t - 1.75).abs() < tolerance(1e-9));
+
+        let bbox = torus.bounding_box(0.0, 1.0).unwrap();
+        assert!((bbox.minimum - Point3::new(-0.25, -1.25, -4.25)).length() < tolerance(1e-9));
+        assert!((bbox.maximum - Point3::new(0.25, 1.25, -1.75)).length() < tolerance(1e-9));
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::vec3::{dot, Point3, Vec3};
+use crate::Float;
+use crate::{HitRecord, Hittable, Ray};
+
+pub struct Triangle {
+    pub v0: Point3,
+    pub v1: Point3,
+    pub v2: Point3,
+    pub mat_ptr: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, mat_ptr: Arc<dyn Material>) -> Self {
+        Triangle {
+            v0,
+            v1,
+            v2,
+            mat_ptr,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    // Möller–Trumbore ray-triangle intersection
+    fn hit(&self, r: &Ray, t_min: Float, t_max: Float) -> Option<HitRecord> {
+        let edge1 = self.v1 - self.v0;
+        let edge2 = self.v2 - self.v0;
+
+        let pvec = r.dir.cross(edge2);
+        let det = dot(edge1, pvec);
+        // the ray is parallel to the triangle's plane
+        if det.abs() < 1e-8 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        // barycentric coordinates of the hit point
+        let tvec = r.orig - self.v0;
+        let u = dot(tvec, pvec) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let qvec = tvec.cross(edge1);
+        let v = dot(r.dir, qvec) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = dot(edge2, qvec) * inv_det;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let outward_normal = edge1.cross(edge2).unit_vector();
+        let hr = HitRecord {
+            p: r.at(t),
+            normal: outward_normal,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            t,
+            u,
+            v,
+            front_face: false,
+            mat_ptr: Arc::clone(&self.mat_ptr),
+            obj_id: 0,
+        };
+        Some(hr.with_face_normal(r, outward_normal))
+    }
+
+    fn bounding_box(&self, _time0: Float, _time1: Float) -> Option<Aabb> {
+        // pad so axis-aligned triangles don't produce a flat box
+        let pad = 0.0001;
+        let min = |a: Float, b: Float, c: Float| a.min(b).min(c) - pad;
+        let max = |a: Float, b: Float, c: Float| a.max(b).max(c) + pad;
+        let (a, b, c) = (self.v0, self.v1, self.v2);
+        Some(Aabb::new(
+            Point3::new(
+                min(a.x(), b.x(), c.x()),
+                min(a.y(), b.y(), c.y()),
+                min(a.z(), b.z(), c.z()),
+            ),
+            Point3::new(
+                max(a.x(), b.x(), c.x()),
+                max(a.y(), b.y(), c.y()),
+                max(a.z(), b.z(), c.z()),
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec3::{Vec3, COLOR_WHITE};
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Point3::new(-1.0, -1.0, -2.0),
+            Point3::new(1.0, -1.0, -2.0),
+            Point3::new(0.0, 1.0, -2.0),
+            Arc::new(Lambertian::new(COLOR_WHITE)),
+        )
+    }
+
+    #[test]
+    fn test_centered_hit() {
+        let r = Ray::new(Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        let rec = triangle().hit(&r, 0.001, Float::INFINITY).unwrap();
+        assert_eq!(rec.t, 2.0);
+        assert_eq!(rec.p, Point3::new(0.0, 0.0, -2.0));
+        assert!(rec.front_face);
+        assert_eq!(rec.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!((rec.u, rec.v), (0.25, 0.5));
+    }
+
+    #[test]
+    fn test_near_edge_hit() {
+        let r = Ray::new(Point3::new(0.0, -0.999, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(triangle().hit(&r, 0.001, Float::INFINITY).is_some());
+
+        let r = Ray::new(Point3::new(0.0, -1.001, 0.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(triangle().hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_parallel_ray_misses() {
+        let r = Ray::new(Point3::new(-5.0, 0.0, -2.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(triangle().hit(&r, 0.001, Float::INFINITY).is_none());
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let bbox = triangle().bounding_box(0.0, 1.0).unwrap();
+        assert!(bbox.minimum.x() < -1.0 && bbox.maximum.x() > 1.0);
+        assert!(bbox.minimum.z() < -2.0 && bbox.maximum.z() > -2.0);
+    }
+}
@@ -1,16 +1,77 @@
-use std::f64::consts::PI;
+use crate::consts::PI;
+use crate::Float;
+use std::cell::RefCell;
 
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+thread_local! {
+    // All sampling goes through this per-thread RNG so that renders can be reproduced by seeding it.
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
 
 // Utility
-pub fn degrees_to_radians(degrees: f64) -> f64 {
+pub fn degrees_to_radians(degrees: Float) -> Float {
     degrees / 180.0 * PI
 }
 
-pub fn random_double() -> f64 {
-    rand::thread_rng().gen::<f64>()
+pub fn radians_to_degrees(radians: Float) -> Float {
+    radians / PI * 180.0
 }
 
-pub fn random_bounded(min: f64, max: f64) -> f64 {
-    rand::thread_rng().gen_range(min..max)
+// Reseeds the current thread's RNG.
+pub fn seed_rng(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+// Combines a seed with another value (e.g. a scanline index) into a new, well-mixed seed.
+pub fn mix_seed(seed: u64, n: u64) -> u64 {
+    // SplitMix64 finalizer
+    let mut z = seed
+        ^ n.wrapping_add(0x9E37_79B9_7F4A_7C15)
+            .wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+// Runs `f` with the current thread's RNG, for code that takes an explicit `Rng`.
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+pub fn random_double() -> Float {
+    RNG.with(|rng| rng.borrow_mut().gen::<Float>())
+}
+
+pub fn random_bounded(min: Float, max: Float) -> Float {
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..max))
+}
+
+// Returns a random integer in [min, max].
+pub fn random_int(min: i32, max: i32) -> i32 {
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..=max))
+}
+
+// Serial stand-ins for the rayon iterator methods the renderer uses, for builds without
+// the `rayon` feature.
+#[cfg(not(feature = "rayon"))]
+pub(crate) mod serial {
+    pub(crate) trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<T: IntoIterator> IntoParallelIterator for T {}
+
+    pub(crate) trait ParallelIterator: Iterator + Sized {
+        fn flat_map_iter<U: IntoIterator, F: FnMut(Self::Item) -> U>(
+            self,
+            f: F,
+        ) -> std::iter::FlatMap<Self, U, F> {
+            self.flat_map(f)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
 }
@@ -1,18 +1,21 @@
+use crate::consts::PI;
 use std::ops::{
     Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
 };
 
+use crate::clamp;
 use rand::Rng;
 
-use crate::util::random_bounded;
+use crate::util::{random_bounded, random_double, with_rng};
+use crate::Float;
 
-pub fn dot(u: Vec3, v: Vec3) -> f64 {
+pub fn dot(u: Vec3, v: Vec3) -> Float {
     u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec3 {
-    e: [f64; 3],
+    e: [Float; 3],
 }
 
 pub type Point3 = Vec3;
@@ -22,30 +25,52 @@ pub const COLOR_BLACK: Color = Color { e: [0.0, 0.0, 0.0] };
 pub const COLOR_WHITE: Color = Color { e: [1.0, 1.0, 1.0] };
 
 impl Vec3 {
-    pub fn new(e0: f64, e1: f64, e2: f64) -> Vec3 {
+    pub fn new(e0: Float, e1: Float, e2: Float) -> Vec3 {
         Vec3 { e: [e0, e1, e2] }
     }
 
-    pub fn x(self) -> f64 {
+    pub fn x(self) -> Float {
         self[0]
     }
 
-    pub fn y(self) -> f64 {
+    pub fn y(self) -> Float {
         self[1]
     }
 
-    pub fn z(self) -> f64 {
+    pub fn z(self) -> Float {
         self[2]
     }
 
-    pub fn dot(self, other: Vec3) -> f64 {
+    // component by axis: 0 => x, 1 => y, 2 => z
+    pub fn axis(self, i: usize) -> Float {
+        self[i]
+    }
+
+    pub fn dot(self, other: Vec3) -> Float {
         self[0] * other[0] + self[1] * other[1] + self[2] * other[2]
     }
 
-    pub fn length(self) -> f64 {
+    pub fn length(self) -> Float {
         self.dot(self).sqrt()
     }
 
+    pub fn length_squared(self) -> Float {
+        self.dot(self)
+    }
+
+    pub fn distance(self, other: Vec3) -> Float {
+        (self - other).length()
+    }
+
+    pub fn distance_squared(self, other: Vec3) -> Float {
+        (self - other).length_squared()
+    }
+
+    // linear interpolation: self at t = 0, other at t = 1
+    pub fn lerp(self, other: Vec3, t: Float) -> Vec3 {
+        self * (1.0 - t) + other * t
+    }
+
     pub fn cross(self, other: Vec3) -> Vec3 {
         Vec3 {
             e: [
@@ -56,8 +81,48 @@ impl Vec3 {
         }
     }
 
+    // component-wise minimum
+    pub fn min(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self[0].min(other[0]),
+            self[1].min(other[1]),
+            self[2].min(other[2]),
+        )
+    }
+
+    // component-wise maximum
+    pub fn max(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self[0].max(other[0]),
+            self[1].max(other[1]),
+            self[2].max(other[2]),
+        )
+    }
+
+    // component-wise clamp of each component into [lo, hi]
+    pub fn clamp(self, lo: Vec3, hi: Vec3) -> Vec3 {
+        self.max(lo).min(hi)
+    }
+
+    // applies `f` to each component
+    pub fn map(self, f: impl Fn(Float) -> Float) -> Vec3 {
+        Vec3::new(f(self[0]), f(self[1]), f(self[2]))
+    }
+
+    // Falls back to the +x axis for (near-)zero vectors instead of producing NaNs.
     pub fn normalized(self) -> Vec3 {
-        self / self.length()
+        self.try_unit_vector()
+            .unwrap_or_else(|| Vec3::new(1.0, 0.0, 0.0))
+    }
+
+    // None if the vector is too short to have a meaningful direction.
+    pub fn try_unit_vector(self) -> Option<Vec3> {
+        let length = self.length();
+        if length < 1e-12 {
+            None
+        } else {
+            Some(self / length)
+        }
     }
 
     // alias of ^
@@ -65,22 +130,35 @@ impl Vec3 {
         self.normalized()
     }
 
+    // The `new_random*` methods use the thread's RNG (see `util::seed_rng`); the `_with`
+    // variants take an explicit one.
     pub fn new_random() -> Vec3 {
-        let mut rng = rand::thread_rng();
-        Vec3::new(rng.gen::<f64>(), rng.gen::<f64>(), rng.gen::<f64>())
+        with_rng(Self::new_random_with)
+    }
+
+    pub fn new_random_with(rng: &mut impl Rng) -> Vec3 {
+        Vec3::new(rng.gen::<Float>(), rng.gen::<Float>(), rng.gen::<Float>())
     }
 
-    pub fn new_random_bounded(min: f64, max: f64) -> Vec3 {
+    pub fn new_random_bounded(min: Float, max: Float) -> Vec3 {
+        with_rng(|rng| Self::new_random_bounded_with(rng, min, max))
+    }
+
+    pub fn new_random_bounded_with(rng: &mut impl Rng, min: Float, max: Float) -> Vec3 {
         Vec3::new(
-            random_bounded(min, max),
-            random_bounded(min, max),
-            random_bounded(min, max),
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
+            rng.gen_range(min..max),
         )
     }
 
     pub fn new_random_in_unit_sphere() -> Vec3 {
+        with_rng(Self::new_random_in_unit_sphere_with)
+    }
+
+    pub fn new_random_in_unit_sphere_with(rng: &mut impl Rng) -> Vec3 {
         loop {
-            let v = Self::new_random();
+            let v = Self::new_random_bounded_with(rng, -1.0, 1.0);
             if dot(v, v) < 1.0 {
                 return v;
             }
@@ -96,7 +174,11 @@ impl Vec3 {
     }
 
     pub fn new_random_unit_vector() -> Vec3 {
-        Self::new_random_in_unit_sphere().unit_vector()
+        with_rng(Self::new_random_unit_vector_with)
+    }
+
+    pub fn new_random_unit_vector_with(rng: &mut impl Rng) -> Vec3 {
+        Self::new_random_in_unit_sphere_with(rng).unit_vector()
     }
 
     pub fn new_random_in_unit_disk() -> Vec3 {
@@ -108,23 +190,69 @@ impl Vec3 {
         }
     }
 
+    // A random direction on the +z hemisphere, distributed proportionally to cos(theta).
+    pub fn random_cosine_direction() -> Vec3 {
+        let r1 = random_double();
+        let r2 = random_double();
+        let z = Float::sqrt(1.0 - r2);
+
+        let phi = 2.0 * PI * r1;
+        let x = phi.cos() * r2.sqrt();
+        let y = phi.sin() * r2.sqrt();
+
+        Vec3::new(x, y, z)
+    }
+
     pub fn near_zero(self) -> bool {
         let s = 1e-8;
         self[0].abs() < s && self[1].abs() < s && self[2].abs() < s
     }
+
+    // Replaces NaN components with 0 and clamps infinite ones to +/-1e8, so that a single bad
+    // sample can't poison a pixel's average.
+    pub fn sanitized(self) -> Vec3 {
+        const LIMIT: Float = 1e8;
+        self.map(|c| {
+            if c.is_nan() {
+                0.0
+            } else {
+                c.clamp(-LIMIT, LIMIT)
+            }
+        })
+    }
+
+    // Perceived brightness of a linear RGB color (Rec. 709 weights).
+    pub fn luminance(self) -> Float {
+        0.2126 * self.x() + 0.7152 * self.y() + 0.0722 * self.z()
+    }
+
+    // Converts an accumulated color sum into 8-bit RGB.
+    // Divides by the number of samples and gamma-corrects for gamma=2.0.
+    pub fn to_rgb8(self, samples_per_pixel: u32) -> [u8; 3] {
+        let c = self.sanitized();
+        let scale = 1.0 / samples_per_pixel as Float;
+        let channel = |c: Float| (256.0 * clamp(Float::sqrt(scale * c), 0.0, 0.999)) as u8;
+        [channel(c[0]), channel(c[1]), channel(c[2])]
+    }
 }
 
 impl Index<usize> for Vec3 {
-    type Output = f64;
+    type Output = Float;
 
-    fn index(&self, index: usize) -> &f64 {
-        &self.e[index]
+    fn index(&self, index: usize) -> &Float {
+        match index {
+            0..=2 => &self.e[index],
+            _ => panic!("Vec3 index out of range: {index} (expected 0, 1 or 2)"),
+        }
     }
 }
 
 impl IndexMut<usize> for Vec3 {
-    fn index_mut(&mut self, index: usize) -> &mut f64 {
-        &mut self.e[index]
+    fn index_mut(&mut self, index: usize) -> &mut Float {
+        match index {
+            0..=2 => &mut self.e[index],
+            _ => panic!("Vec3 index out of range: {index} (expected 0, 1 or 2)"),
+        }
     }
 }
 
@@ -139,7 +267,7 @@ impl Add for Vec3 {
 }
 
 impl AddAssign for Vec3 {
-    fn add_assign(&mut self, other: Vec3) -> () {
+    fn add_assign(&mut self, other: Vec3) {
         *self = Vec3 {
             e: [self[0] + other[0], self[1] + other[1], self[2] + other[2]],
         };
@@ -157,32 +285,32 @@ impl Sub for Vec3 {
 }
 
 impl SubAssign for Vec3 {
-    fn sub_assign(&mut self, other: Vec3) -> () {
+    fn sub_assign(&mut self, other: Vec3) {
         *self = Vec3 {
             e: [self[0] - other[0], self[1] - other[1], self[2] - other[2]],
         };
     }
 }
 
-impl Mul<f64> for Vec3 {
+impl Mul<Float> for Vec3 {
     type Output = Vec3;
 
-    fn mul(self, other: f64) -> Vec3 {
+    fn mul(self, other: Float) -> Vec3 {
         Vec3 {
             e: [self[0] * other, self[1] * other, self[2] * other],
         }
     }
 }
 
-impl MulAssign<f64> for Vec3 {
-    fn mul_assign(&mut self, other: f64) -> () {
+impl MulAssign<Float> for Vec3 {
+    fn mul_assign(&mut self, other: Float) {
         *self = Vec3 {
             e: [self[0] * other, self[1] * other, self[2] * other],
         };
     }
 }
 
-impl Mul<Vec3> for f64 {
+impl Mul<Vec3> for Float {
     type Output = Vec3;
 
     fn mul(self, other: Vec3) -> Vec3 {
@@ -202,18 +330,28 @@ impl Mul<Vec3> for Vec3 {
     }
 }
 
-impl Div<f64> for Vec3 {
+impl Div<Float> for Vec3 {
     type Output = Vec3;
 
-    fn div(self, other: f64) -> Vec3 {
+    fn div(self, other: Float) -> Vec3 {
         Vec3 {
             e: [self[0] / other, self[1] / other, self[2] / other],
         }
     }
 }
 
-impl DivAssign<f64> for Vec3 {
-    fn div_assign(&mut self, other: f64) -> () {
+impl Div<Vec3> for Vec3 {
+    type Output = Vec3;
+
+    fn div(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            e: [self[0] / other[0], self[1] / other[1], self[2] / other[2]],
+        }
+    }
+}
+
+impl DivAssign<Float> for Vec3 {
+    fn div_assign(&mut self, other: Float) {
         *self = Vec3 {
             e: [self[0] / other, self[1] / other, self[2] / other],
         };
@@ -227,3 +365,178 @@ impl Neg for Vec3 {
         self * -1.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tolerance;
+
+    #[test]
+    fn test_random_in_unit_sphere_is_centered() {
+        let n = 5000;
+        let mut sum = Vec3::new(0.0, 0.0, 0.0);
+        for _ in 0..n {
+            let v = Vec3::new_random_in_unit_sphere();
+            assert!(dot(v, v) < 1.0);
+            sum += v;
+        }
+        let mean = sum / n as Float;
+        for i in 0..3 {
+            assert!(mean[i].abs() < 0.05, "mean {:?} is biased", mean);
+        }
+    }
+
+    #[test]
+    fn test_to_rgb8_gamma_corrects() {
+        let c = Color::new(0.25, 0.0, 1.0);
+        assert_eq!(c.to_rgb8(1), [128, 0, 255]);
+        // the sum of 4 samples averaging 0.25
+        assert_eq!((c * 4.0).to_rgb8(4), [128, 0, 255]);
+    }
+
+    #[test]
+    fn test_index() {
+        let mut v = Vec3::new(1.0, 2.0, 3.0);
+        assert_eq!(v[0], 1.0);
+        assert_eq!(v.axis(1), 2.0);
+        v[2] = 9.0;
+        assert_eq!(v.z(), 9.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Vec3 index out of range: 3")]
+    fn test_index_out_of_range() {
+        let v = Vec3::new(1.0, 2.0, 3.0);
+        let _ = v[3];
+    }
+
+    #[test]
+    fn test_compound_assignment() {
+        let v = Vec3::new(1.0, -2.0, 3.0);
+        let w = Vec3::new(0.5, 4.0, -1.0);
+
+        let mut a = v;
+        a += w;
+        assert_eq!(a, v + w);
+
+        let mut a = v;
+        a -= w;
+        assert_eq!(a, v - w);
+
+        let mut a = v;
+        a *= 2.0;
+        assert_eq!(a, Vec3::new(2.0, -4.0, 6.0));
+
+        let mut a = v;
+        a /= 2.0;
+        assert_eq!(a, v / 2.0);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let a = Vec3::new(-1.0, 2.0, -3.0);
+        let b = Vec3::new(1.0, -2.0, -4.0);
+        assert_eq!(a.min(b), Vec3::new(-1.0, -2.0, -4.0));
+        assert_eq!(a.max(b), Vec3::new(1.0, 2.0, -3.0));
+        assert_eq!(a.min(a), a);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let lo = Vec3::new(0.0, 0.0, 0.0);
+        let hi = Vec3::new(1.0, 1.0, 1.0);
+        let v = Vec3::new(-0.5, 0.25, 1.5);
+        assert_eq!(v.clamp(lo, hi), Vec3::new(0.0, 0.25, 1.0));
+    }
+
+    #[test]
+    fn test_map() {
+        let v = Vec3::new(-1.0, 4.0, -9.0);
+        assert_eq!(v.map(Float::abs), Vec3::new(1.0, 4.0, 9.0));
+        assert_eq!(v.map(|x| x * 2.0), v * 2.0);
+    }
+
+    #[test]
+    fn test_try_unit_vector() {
+        let v = Vec3::new(3.0, 0.0, -4.0).try_unit_vector().unwrap();
+        assert_eq!(v, Vec3::new(0.6, 0.0, -0.8));
+
+        assert_eq!(Vec3::new(0.0, 0.0, 0.0).try_unit_vector(), None);
+        assert_eq!(Vec3::new(1e-13, 0.0, 1e-14).try_unit_vector(), None);
+    }
+
+    #[test]
+    fn test_unit_vector_of_zero_is_finite() {
+        for v in [Vec3::new(0.0, 0.0, 0.0), Vec3::new(1e-300, 1e-300, 0.0)] {
+            let u = v.unit_vector();
+            assert!(u.x().is_finite() && u.y().is_finite() && u.z().is_finite());
+            assert!((u.length() - 1.0).abs() < tolerance(1e-12));
+        }
+    }
+
+    #[test]
+    fn test_lerp() {
+        let a = Vec3::new(1.0, -2.0, 3.0);
+        let b = Vec3::new(3.0, 2.0, -1.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), Vec3::new(2.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_distance() {
+        let a = Vec3::new(1.0, 1.0, 1.0);
+        let b = Vec3::new(4.0, 5.0, 1.0);
+        assert_eq!(a.distance(b), 5.0);
+        assert_eq!(b.distance(a), 5.0);
+        assert_eq!(a.distance_squared(b), 25.0);
+        assert_eq!(a.distance(a), 0.0);
+    }
+
+    #[test]
+    fn test_sanitized_average() {
+        let samples = [
+            Color::new(0.5, 0.5, 0.5),
+            Color::new(Float::NAN, 0.5, 0.5),
+            Color::new(0.5, 0.5, 0.5),
+            Color::new(0.5, Float::INFINITY, 0.5),
+        ];
+        let sum = samples
+            .iter()
+            .fold(COLOR_BLACK, |sum, &s| sum + s.sanitized());
+        assert!(sum.x().is_finite() && sum.y().is_finite() && sum.z().is_finite());
+        assert_eq!(sum.x(), 1.5);
+        assert_eq!(sum.z(), 2.0);
+        assert_eq!(sum.to_rgb8(4), [156, 255, 181]);
+        assert_eq!(Color::new(Float::NAN, 0.0, 0.0).to_rgb8(1), [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_random_with_seeded_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let draw = || {
+            let mut rng = StdRng::seed_from_u64(9);
+            [
+                Vec3::new_random_with(&mut rng),
+                Vec3::new_random_bounded_with(&mut rng, -2.0, 2.0),
+                Vec3::new_random_in_unit_sphere_with(&mut rng),
+                Vec3::new_random_unit_vector_with(&mut rng),
+            ]
+        };
+        let first = draw();
+        assert_eq!(first, draw());
+        assert!(first[2].length() < 1.0);
+        assert!((first[3].length() - 1.0).abs() < tolerance(1e-12));
+    }
+
+    #[test]
+    fn test_random_wrappers_use_seeded_thread_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        crate::util::seed_rng(9);
+        let wrapped = Vec3::new_random_unit_vector();
+        let explicit = Vec3::new_random_unit_vector_with(&mut StdRng::seed_from_u64(9));
+        assert_eq!(wrapped, explicit);
+    }
+}